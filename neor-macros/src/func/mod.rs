@@ -1,95 +1,133 @@
 #[cfg(test)]
 mod tests;
 
-use proc_macro2::{TokenStream, TokenTree};
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use std::iter::FromIterator;
-use syn::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Expr, ExprClosure, Ident, Pat, PatType, ReturnType, Token, Type};
 
-#[derive(Debug)]
 pub(super) struct Func {
-    mv: Option<Ident>,
-    args: Vec<Ident>,
-    body: TokenStream,
+    attrs: Vec<Attribute>,
+    mv: Option<Token![move]>,
+    args: Vec<Pat>,
+    output: ReturnType,
+    body: Box<Expr>,
 }
 
-impl Func {
-    pub(super) fn new(input: TokenStream) -> Self {
-        let mut iter = input.into_iter();
-        let mv = iter.next().map(first).unwrap();
-        if mv.is_some() {
-            iter.next().filter(is_pipe).unwrap();
+impl Parse for Func {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let closure: ExprClosure = input.parse().map_err(|error| {
+            Error::new(error.span(), "expected a closure, e.g. `|doc| doc.g(\"id\")`")
+        })?;
+
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the closure"));
         }
-        let mut args = Vec::new();
-        while let Some(token) = iter.next() {
-            if is_pipe(&token) {
-                break;
-            }
-            args.push(ident(token));
-            let token = iter.next().unwrap();
-            if is_pipe(&token) {
-                break;
-            }
-            assert_comma(&token);
+
+        let ExprClosure {
+            attrs,
+            asyncness,
+            movability,
+            capture,
+            inputs,
+            output,
+            body,
+            ..
+        } = closure;
+
+        if let Some(asyncness) = asyncness {
+            return Err(Error::new(
+                asyncness.span(),
+                "`func!` closures cannot be async, the body is evaluated once to build the query",
+            ));
         }
-        let body = TokenStream::from_iter(iter);
-        Self { mv, args, body }
+
+        if let Some(movability) = movability {
+            return Err(Error::new(
+                movability.span(),
+                "`func!` closures cannot be static",
+            ));
+        }
+
+        let args = inputs
+            .into_iter()
+            .map(arg)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            attrs,
+            mv: capture,
+            args,
+            output,
+            body,
+        })
     }
+}
 
+impl Func {
     pub(super) fn process(self) -> TokenStream {
-        let Self { mv, args, body } = self;
-        let mut header = quote!(#mv |);
-        let mut params = TokenStream::new();
+        let Self {
+            attrs,
+            mv,
+            args,
+            output,
+            body,
+        } = self;
+        let closure = Ident::new("closure", Span::mixed_site());
+        let ids = Ident::new("ids", Span::mixed_site());
+        let func = Ident::new("func", Span::mixed_site());
         let func_args = args.len();
-        for (i, arg) in args.into_iter().enumerate() {
-            let var = quote!(neor::Command::var(*ids.get(#i).unwrap()));
-            if i == func_args - 1 {
-                header.extend(quote!(#arg: neor::Command));
-                params.extend(quote!(#var));
-            } else {
-                header.extend(quote!(#arg: neor::Command, ));
-                params.extend(quote!(#var, ));
-            }
-        }
-        header.extend(quote!(|));
-        let closure = quote!(#header #body);
+        let params = (0..func_args).map(|i| quote!(neor::Command::var(#ids[#i])));
+
         quote!({
-            let closure = #closure;
-            let mut ids = Vec::with_capacity(#func_args);
+            #(#attrs)*
+            let #closure = #mv |#(#args: neor::Command),*| #output #body;
+            let mut #ids = Vec::with_capacity(#func_args);
             for _ in 0..#func_args {
-                let id = neor::var_counter();
-                ids.push(id);
+                #ids.push(neor::var_counter());
             }
-            let func = closure(#params);
-            neor::Func::new(ids, func)
+            let #func = #closure(#(#params),*);
+            neor::Func::new(#ids, #func)
         })
     }
 }
 
-fn first(token: TokenTree) -> Option<Ident> {
-    if is_pipe(&token) {
-        return None;
-    }
-    match token {
-        TokenTree::Ident(ident) if ident == "move" => Some(ident),
-        _ => panic!("invalid closure"),
-    }
-}
+// Every parameter of a ReQL function is a `Command`, so the only thing
+// a pattern can do is bind (or ignore) it. An explicit type is allowed
+// for readability but must name `Command`.
+fn arg(pat: Pat) -> syn::Result<Pat> {
+    let pat = match pat {
+        Pat::Type(PatType { pat, ty, .. }) => {
+            assert_command(&ty)?;
+            *pat
+        }
+        pat => pat,
+    };
 
-fn ident(token: TokenTree) -> Ident {
-    match token {
-        TokenTree::Ident(ident) => ident,
-        _ => panic!("invalid closure"),
+    match pat {
+        Pat::Ident(ref ident) if ident.subpat.is_none() => Ok(pat),
+        Pat::Wild(_) => Ok(pat),
+        pat => Err(Error::new(
+            pat.span(),
+            "expected an identifier or `_`, `func!` parameters are always `neor::Command`",
+        )),
     }
 }
 
-fn is_pipe(token: &TokenTree) -> bool {
-    matches!(token, TokenTree::Punct(punct) if punct.as_char() == '|')
-}
-
-fn assert_comma(token: &TokenTree) {
-    match token {
-        TokenTree::Punct(punct) if punct.as_char() == ',' => {}
-        _ => panic!("invalid closure"),
+fn assert_command(ty: &Type) -> syn::Result<()> {
+    if let Type::Path(path) = ty {
+        if path.qself.is_none() {
+            if let Some(segment) = path.path.segments.last() {
+                if segment.ident == "Command" && segment.arguments.is_empty() {
+                    return Ok(());
+                }
+            }
+        }
     }
+
+    Err(Error::new(
+        ty.span(),
+        "`func!` parameters are always `neor::Command`",
+    ))
 }
@@ -1,24 +1,95 @@
 use super::*;
 
+fn expand(input: TokenStream) -> String {
+    syn::parse2::<Func>(input).unwrap().process().to_string()
+}
+
+fn error(input: TokenStream) -> String {
+    match syn::parse2::<Func>(input) {
+        Ok(_) => panic!("the closure should have been rejected"),
+        Err(error) => error.to_string(),
+    }
+}
+
 #[test]
 fn with_move() {
-    Func::new(quote!(move |doc| {
+    expand(quote!(move |doc| {
         doc.get_field("author").bracket("name")
-    }))
-    .process();
+    }));
 }
 
 #[test]
 fn with_no_arg() {
-    Func::new(quote!(|| r.expr("Hello world!"))).process();
+    let output = expand(quote!(|| r.expr("Hello world!")));
+
+    assert!(output.contains("Vec :: with_capacity (0usize)"));
 }
 
 #[test]
 fn with_one_arg() {
-    Func::new(quote!(|doc| { doc.get_field("author").bracket("name") })).process();
+    expand(quote!(|doc| { doc.get_field("author").bracket("name") }));
 }
 
 #[test]
 fn with_multiple_args() {
-    Func::new(quote!(|with, multiple, args| r.expr(with, multiple, args))).process();
+    let output = expand(quote!(|with, multiple, args| r.expr(with, multiple, args)));
+
+    assert!(output.contains("Vec :: with_capacity (3usize)"));
+}
+
+#[test]
+fn with_typed_args() {
+    let output = expand(quote!(|acc: Command, doc: neor::Command| acc + doc));
+
+    assert!(output.contains("acc : neor :: Command , doc : neor :: Command"));
+}
+
+#[test]
+fn with_patterns() {
+    let output = expand(quote!(|_, mut doc| doc));
+
+    assert!(output.contains("_ : neor :: Command , mut doc : neor :: Command"));
+}
+
+#[test]
+fn with_return_type() {
+    expand(quote!(|doc| -> Command { doc.g("id") }));
+}
+
+#[test]
+fn with_nested_func() {
+    let output = expand(quote!(|doc| doc.g("posts").map(func!(|post| post.g("title")))));
+
+    assert!(output.contains("func ! (| post | post . g (\"title\"))"));
+}
+
+#[test]
+fn error_not_a_closure() {
+    assert!(error(quote!(doc.g("id"))).starts_with("expected a closure"));
+}
+
+#[test]
+fn error_trailing_tokens() {
+    assert_eq!(
+        error(quote!(|doc| doc, 1)),
+        "unexpected tokens after the closure"
+    );
+}
+
+#[test]
+fn error_wrong_type() {
+    assert_eq!(
+        error(quote!(|doc: String| doc)),
+        "`func!` parameters are always `neor::Command`"
+    );
+}
+
+#[test]
+fn error_tuple_pattern() {
+    assert!(error(quote!(|(left, right)| left)).starts_with("expected an identifier or `_`"));
+}
+
+#[test]
+fn error_async() {
+    assert!(error(quote!(async |doc| doc)).starts_with("`func!` closures cannot be async"));
 }
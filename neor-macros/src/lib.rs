@@ -1,13 +1,26 @@
 use func::Func;
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
 mod func;
 mod geometry;
 mod options;
 
+/// Build a ReQL function from a closure.
+///
+/// Every parameter is a `neor::Command`. Parameters may be
+/// identifiers, `mut` identifiers or `_`, optionally annotated
+/// with `: Command`. `func!` may be nested inside the body.
+///
+/// ```text
+/// func!(|| r.expr(1))
+/// func!(|doc| doc.g("age").gt(18))
+/// func!(|acc: Command, _| acc + 1)
+/// func!(move |doc| doc.g("posts").map(func!(|post| post.g("title"))))
+/// ```
 #[proc_macro]
 pub fn func(input: TokenStream) -> TokenStream {
-    Func::new(input.into()).process().into()
+    parse_macro_input!(input as Func).process().into()
 }
 
 #[proc_macro_derive(CommandOptions)]
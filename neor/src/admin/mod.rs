@@ -0,0 +1,142 @@
+//! Typed access to the `rethinkdb` system database.
+//!
+//! Each function returns the query reading one of the
+//! [system tables](https://rethinkdb.com/docs/system-tables/),
+//! whose rows can be parsed into the matching struct.
+//!
+//! ```
+//! use neor::admin::{self, Job};
+//! use neor::{r, Converter, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     let jobs: Vec<Job> = admin::jobs()
+//!         .run(&conn)
+//!         .await?
+//!         .unwrap()
+//!         .parse()?;
+//!
+//!     assert!(!jobs.is_empty());
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{r, Command};
+
+pub use tables::*;
+
+mod tables;
+
+/// The name of the system database
+pub const SYSTEM_DB: &str = "rethinkdb";
+
+/// The tables of the `rethinkdb` system database
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum SystemTable {
+    TableConfig,
+    TableStatus,
+    ServerConfig,
+    ServerStatus,
+    ClusterConfig,
+    CurrentIssues,
+    Jobs,
+    Stats,
+    Logs,
+    Users,
+    Permissions,
+    DbConfig,
+}
+
+impl SystemTable {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TableConfig => "table_config",
+            Self::TableStatus => "table_status",
+            Self::ServerConfig => "server_config",
+            Self::ServerStatus => "server_status",
+            Self::ClusterConfig => "cluster_config",
+            Self::CurrentIssues => "current_issues",
+            Self::Jobs => "jobs",
+            Self::Stats => "stats",
+            Self::Logs => "logs",
+            Self::Users => "users",
+            Self::Permissions => "permissions",
+            Self::DbConfig => "db_config",
+        }
+    }
+}
+
+impl fmt::Display for SystemTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Select a table of the `rethinkdb` system database.
+pub fn table(table: SystemTable) -> Command {
+    r.db(SYSTEM_DB).table(table.name())
+}
+
+/// Configuration of the tables, parsed as [TableConfig].
+pub fn table_config() -> Command {
+    table(SystemTable::TableConfig)
+}
+
+/// Status of the tables, parsed as [TableStatus].
+pub fn table_status() -> Command {
+    table(SystemTable::TableStatus)
+}
+
+/// Configuration of the servers, parsed as [ServerConfig].
+pub fn server_config() -> Command {
+    table(SystemTable::ServerConfig)
+}
+
+/// Status of the servers, parsed as [ServerStatus].
+pub fn server_status() -> Command {
+    table(SystemTable::ServerStatus)
+}
+
+/// Cluster-wide settings, parsed as [ClusterConfig].
+pub fn cluster_config() -> Command {
+    table(SystemTable::ClusterConfig)
+}
+
+/// Problems detected in the cluster, parsed as [Issue].
+pub fn current_issues() -> Command {
+    table(SystemTable::CurrentIssues)
+}
+
+/// Queries and background tasks being run, parsed as [Job].
+pub fn jobs() -> Command {
+    table(SystemTable::Jobs)
+}
+
+/// Statistics of the cluster, servers and tables, parsed as [Stats].
+pub fn stats() -> Command {
+    table(SystemTable::Stats)
+}
+
+/// Log entries of the servers, parsed as [Log].
+pub fn logs() -> Command {
+    table(SystemTable::Logs)
+}
+
+/// User accounts, parsed as [User].
+pub fn users() -> Command {
+    table(SystemTable::Users)
+}
+
+/// Permissions granted to the users, parsed as [UserPermission].
+pub fn permissions() -> Command {
+    table(SystemTable::Permissions)
+}
+
+/// Configuration of the databases, parsed as [DbConfig].
+pub fn db_config() -> Command {
+    table(SystemTable::DbConfig)
+}
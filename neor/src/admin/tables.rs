@@ -0,0 +1,476 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::arguments::{Durability, Permission};
+use crate::types::{ShardType, StatusResponseStatus, Time};
+
+/// A row of the `table_config` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct TableConfig {
+    /// the UUID of the table.
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    /// the name of the database the table is in.
+    pub db: Cow<'static, str>,
+    pub primary_key: Cow<'static, str>,
+    pub shards: Vec<ShardType<Cow<'static, str>>>,
+    /// the names of the secondary indexes.
+    pub indexes: Vec<Cow<'static, str>>,
+    pub write_acks: WriteAcks,
+    pub durability: Durability,
+    /// the write hook function, if any.
+    pub write_hook: Option<Value>,
+}
+
+/// The number of replicas that must acknowledge a write
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum WriteAcks {
+    Mode(WriteAcksMode),
+    /// requirements set per group of replicas.
+    Custom(Vec<Value>),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteAcksMode {
+    Majority,
+    Single,
+}
+
+/// A row of the `table_status` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct TableStatus {
+    /// the UUID of the table.
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub db: Cow<'static, str>,
+    pub status: StatusResponseStatus,
+    /// one entry for each shard in `table_config`,
+    /// `None` when the table is unavailable.
+    pub shards: Option<Vec<TableStatusShard>>,
+    /// the name of the server acting as Raft leader for the table.
+    pub raft_leader: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct TableStatusShard {
+    pub primary_replicas: Vec<Cow<'static, str>>,
+    pub replicas: Vec<ReplicaStatus>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct ReplicaStatus {
+    pub server: Cow<'static, str>,
+    /// `ready`, `transitioning`, `backfilling`, `disconnected`,
+    /// `waiting_for_primary` or `waiting_for_quorum`.
+    pub state: Cow<'static, str>,
+}
+
+/// A row of the `server_config` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ServerConfig {
+    /// the UUID of the server.
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub tags: Vec<Cow<'static, str>>,
+    pub cache_size_mb: CacheSize,
+}
+
+/// The size of the page cache of a server
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheSize {
+    /// chosen by the server from the available memory.
+    Auto,
+    /// in megabytes.
+    Mb(f64),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum CacheSizeRepr<'a> {
+    Mb(f64),
+    Other(Cow<'a, str>),
+}
+
+impl Serialize for CacheSize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Self::Auto => CacheSizeRepr::Other(Cow::Borrowed("auto")),
+            Self::Mb(size) => CacheSizeRepr::Mb(size),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CacheSize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match CacheSizeRepr::deserialize(deserializer)? {
+            CacheSizeRepr::Mb(size) => Ok(Self::Mb(size)),
+            CacheSizeRepr::Other(size) if size == "auto" => Ok(Self::Auto),
+            CacheSizeRepr::Other(size) => Err(serde::de::Error::custom(format!(
+                "invalid cache size `{}`",
+                size
+            ))),
+        }
+    }
+}
+
+/// A row of the `server_status` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ServerStatus {
+    /// the UUID of the server.
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    pub network: ServerNetwork,
+    pub process: ServerProcess,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ServerNetwork {
+    pub hostname: Cow<'static, str>,
+    pub cluster_port: u16,
+    pub reql_port: u16,
+    /// the port of the web UI, or a string when it is disabled.
+    pub http_admin_port: Value,
+    pub canonical_addresses: Vec<CanonicalAddress>,
+    /// whether this server is connected to each other server.
+    pub connected_to: HashMap<Cow<'static, str>, bool>,
+    pub time_connected: Time,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct CanonicalAddress {
+    pub host: Cow<'static, str>,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ServerProcess {
+    pub argv: Vec<Cow<'static, str>>,
+    pub cache_size_mb: f64,
+    pub pid: u32,
+    pub time_started: Time,
+    pub version: Cow<'static, str>,
+}
+
+/// A row of the `cluster_config` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ClusterConfig {
+    /// always `heartbeat`.
+    pub id: Cow<'static, str>,
+    /// the number of seconds before a server is considered disconnected.
+    pub heartbeat_timeout_secs: f64,
+}
+
+/// A row of the `current_issues` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Issue {
+    pub id: Cow<'static, str>,
+    #[serde(rename = "type")]
+    pub typ: IssueType,
+    /// whether the issue makes data unavailable.
+    pub critical: bool,
+    /// details depending on the type of the issue.
+    pub info: Value,
+    pub description: Cow<'static, str>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum IssueType {
+    LogWriteError,
+    ServerNameCollision,
+    DbNameCollision,
+    TableNameCollision,
+    OutdatedIndex,
+    TableAvailability,
+    MemoryError,
+    NonTransitiveConnectivity,
+    #[serde(other)]
+    Other,
+}
+
+/// A row of the `jobs` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Job {
+    /// the type of the job followed by its UUID.
+    pub id: Vec<Value>,
+    #[serde(rename = "type")]
+    pub typ: JobType,
+    pub duration_sec: Option<f64>,
+    /// details depending on the type of the job.
+    pub info: Value,
+    /// the names of the servers running the job.
+    pub servers: Vec<Cow<'static, str>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum JobType {
+    Query,
+    DiskCompaction,
+    IndexConstruction,
+    Backfill,
+    #[serde(other)]
+    Other,
+}
+
+/// A row of the `stats` system table
+///
+/// The `id` tells what the statistics are about:
+/// `["cluster"]`, `["server", server_id]`, `["table", table_id]`
+/// or `["table_server", table_id, server_id]`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Stats {
+    pub id: Vec<Cow<'static, str>>,
+    pub server: Option<Cow<'static, str>>,
+    pub db: Option<Cow<'static, str>>,
+    pub table: Option<Cow<'static, str>>,
+    pub query_engine: Option<QueryEngineStats>,
+    pub storage_engine: Option<StorageEngineStats>,
+    /// set instead of the statistics when the server is unreachable.
+    pub error: Option<Cow<'static, str>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct QueryEngineStats {
+    pub queries_per_sec: Option<f64>,
+    pub queries_total: Option<u64>,
+    pub read_docs_per_sec: Option<f64>,
+    pub read_docs_total: Option<u64>,
+    pub written_docs_per_sec: Option<f64>,
+    pub written_docs_total: Option<u64>,
+    pub client_connections: Option<u64>,
+    pub clients_active: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct StorageEngineStats {
+    pub cache: CacheStats,
+    pub disk: DiskStats,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct CacheStats {
+    pub in_use_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct DiskStats {
+    pub read_bytes_per_sec: f64,
+    pub read_bytes_total: u64,
+    pub written_bytes_per_sec: f64,
+    pub written_bytes_total: u64,
+    pub space_usage: SpaceUsage,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct SpaceUsage {
+    pub metadata_bytes: u64,
+    pub data_bytes: u64,
+    pub garbage_bytes: u64,
+    pub preallocated_bytes: u64,
+}
+
+/// A row of the `logs` system table
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct Log {
+    /// the time of the entry followed by a unique identifier.
+    pub id: Vec<Value>,
+    pub level: LogLevel,
+    pub message: Cow<'static, str>,
+    /// the name of the server the entry comes from.
+    pub server: Cow<'static, str>,
+    pub timestamp: Time,
+    /// the number of seconds the server had been running.
+    pub uptime: f64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warn,
+    Error,
+}
+
+/// A row of the `users` system table
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct User {
+    /// the name of the user.
+    pub id: Cow<'static, str>,
+    /// whether the user has a password, which is never returned.
+    pub password: bool,
+}
+
+/// A row of the `permissions` system table
+///
+/// The scope is global when `database` is `None`,
+/// and a database when `table` is `None`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub struct UserPermission {
+    /// the user followed by the UUIDs of the database and table.
+    pub id: Vec<Cow<'static, str>>,
+    pub user: Cow<'static, str>,
+    pub database: Option<Cow<'static, str>>,
+    pub table: Option<Cow<'static, str>>,
+    pub permissions: Permission,
+}
+
+impl UserPermission {
+    pub fn scope(&self) -> PermissionScope {
+        match (&self.database, &self.table) {
+            (Some(database), Some(table)) => PermissionScope::Table {
+                database: database.clone(),
+                table: table.clone(),
+            },
+            (Some(database), None) => PermissionScope::Db {
+                database: database.clone(),
+            },
+            _ => PermissionScope::Global,
+        }
+    }
+}
+
+/// Where a [UserPermission] applies
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PermissionScope {
+    Global,
+    Db {
+        database: Cow<'static, str>,
+    },
+    Table {
+        database: Cow<'static, str>,
+        table: Cow<'static, str>,
+    },
+}
+
+/// A row of the `db_config` system table
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub struct DbConfig {
+    /// the UUID of the database.
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_table_config() {
+        let config: TableConfig = serde_json::from_value(json!({
+            "db": "test",
+            "durability": "hard",
+            "id": "31c92680-f70c-4a4b-a49e-b238eb12c023",
+            "indexes": ["timestamp"],
+            "name": "tv_shows",
+            "primary_key": "id",
+            "shards": [{
+                "primary_replica": "a",
+                "replicas": ["a", "b"],
+                "nonvoting_replicas": []
+            }],
+            "write_acks": "majority",
+            "write_hook": null
+        }))
+        .unwrap();
+
+        assert_eq!(config.write_acks, WriteAcks::Mode(WriteAcksMode::Majority));
+        assert_eq!(config.shards[0].replicas.len(), 2);
+    }
+
+    #[test]
+    fn test_server_config() {
+        let auto: ServerConfig = serde_json::from_value(json!({
+            "id": "de8b75d1-3184-48f0-b1ef-99a9b04e2f8b",
+            "name": "rethinkdb_srv1",
+            "tags": ["default"],
+            "cache_size_mb": "auto"
+        }))
+        .unwrap();
+        let fixed: CacheSize = serde_json::from_value(json!(512)).unwrap();
+
+        assert_eq!(auto.cache_size_mb, CacheSize::Auto);
+        assert_eq!(fixed, CacheSize::Mb(512.));
+        assert_eq!(
+            serde_json::to_value(CacheSize::Auto).unwrap(),
+            json!("auto")
+        );
+        assert!(serde_json::from_value::<CacheSize>(json!("lots")).is_err());
+    }
+
+    #[test]
+    fn test_job() {
+        let job: Job = serde_json::from_value(json!({
+            "duration_sec": 0.12,
+            "id": ["query", "72789a22-a3b3-4d52-a0cf-8d1a3c6e3b25"],
+            "info": {"client_address": "127.0.0.1", "client_port": 55034},
+            "servers": ["rethinkdb_srv1"],
+            "type": "query"
+        }))
+        .unwrap();
+        let unknown: JobType = serde_json::from_value(json!("garbage_collection")).unwrap();
+
+        assert_eq!(job.typ, JobType::Query);
+        assert_eq!(unknown, JobType::Other);
+    }
+
+    #[test]
+    fn test_user_permission() {
+        let permission: UserPermission = serde_json::from_value(json!({
+            "database": "field_notes",
+            "id": ["bob", "8b3fa6ee-2a5a-4ac3-9a9b-0b3d8c2e1b5a"],
+            "permissions": {"read": true, "write": false},
+            "user": "bob"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            permission.scope(),
+            PermissionScope::Db {
+                database: "field_notes".into()
+            }
+        );
+        assert_eq!(permission.permissions.read, Some(true));
+        assert_eq!(permission.permissions.config, None);
+    }
+}
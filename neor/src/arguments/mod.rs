@@ -3,6 +3,8 @@ use std::{borrow::Cow, collections::HashMap};
 use neor_macros::CommandOptions;
 use serde::{Deserialize, Serialize};
 
+use crate::Func;
+
 pub use options::*;
 pub use return_changes::ReturnChanges;
 
@@ -12,7 +14,9 @@ mod return_changes;
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Args<T>(pub T);
 
-#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, PartialOrd, CommandOptions,
+)]
 pub struct Permission {
     /// allows reading the data in tables.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -127,13 +131,14 @@ pub enum Unit {
     InternationalFoot,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 #[serde(untagged)]
 #[non_exhaustive]
 pub enum Interleave {
     Bool(bool),
     FieldName(&'static str),
-    // Function
+    #[serde(skip)]
+    Func(Func),
 }
 
 /// Controls how change notifications are batched
@@ -159,3 +164,80 @@ pub enum CoerceType {
     Object,
     Binary,
 }
+
+/// The format of the result of [http](crate::r::http)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultFormat {
+    /// always return a string.
+    Text,
+    /// parse the result as JSON, raising an error on failure.
+    Json,
+    /// parse the result as Padded JSON.
+    Jsonp,
+    /// return a binary object.
+    Binary,
+    /// parse the result based on its `Content-Type`.
+    /// This is the default.
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    Basic,
+    Digest,
+}
+
+/// Authentication used by [http](crate::r::http)
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HttpAuth {
+    /// `AuthType::Basic` (the default) or `AuthType::Digest`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub typ: Option<AuthType>,
+    pub user: Cow<'static, str>,
+    pub pass: Cow<'static, str>,
+}
+
+impl HttpAuth {
+    pub fn basic(user: impl Into<Cow<'static, str>>, pass: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            typ: Some(AuthType::Basic),
+            user: user.into(),
+            pass: pass.into(),
+        }
+    }
+
+    pub fn digest(user: impl Into<Cow<'static, str>>, pass: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            typ: Some(AuthType::Digest),
+            user: user.into(),
+            pass: pass.into(),
+        }
+    }
+}
+
+/// How [http](crate::r::http) finds the next page of results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Page {
+    /// follow the `rel="next"` URL of the `Link` header.
+    #[serde(rename = "link-next")]
+    LinkNext,
+    /// a function taking an object with the `params`, `header`
+    /// and `body` of the last response, and returning the URL
+    /// of the next page or `null` to stop.
+    #[serde(skip)]
+    Func(Func),
+}
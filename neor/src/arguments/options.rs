@@ -1,12 +1,14 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::time::Duration;
 
 use neor_macros::CommandOptions;
 use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 use crate::cmd::run::Db;
 use crate::constants::DEFAULT_RETHINKDB_DBNAME;
-use crate::Session;
+use crate::{Func, Session};
 
 use super::*;
 
@@ -101,6 +103,21 @@ pub struct BetweenOption {
     pub right_bound: Option<Status>,
 }
 
+#[derive(Debug, Clone, Default, CommandOptions)]
+#[non_exhaustive]
+pub struct BatcherOption {
+    /// options of the `get_all` queries, such as the index to look up.
+    pub get_all: GetAllOption,
+    /// the maximum number of keys per query. Defaults to 100.
+    pub max_batch_size: Option<usize>,
+    /// how long the first key waits for others before the query is run.
+    /// Defaults to 1 millisecond.
+    pub delay: Option<Duration>,
+    /// whether the documents are kept, so that a key is only
+    /// loaded once. Defaults to `true`.
+    pub cache: Option<bool>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
 pub struct DeleteOption {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -175,12 +192,18 @@ impl FilterOption {
     }
 }
 
-// #[derive(Debug, Clone, Serialize, Default)]
-// #[non_exhaustive]
-// pub struct FoldOption {
-//     pub emit: Option<Command>,
-//     pub final_emit: Option<Command>,
-// }
+#[derive(Debug, Clone, Default, CommandOptions)]
+#[non_exhaustive]
+pub struct FoldOption {
+    /// a function taking the accumulator, the current row and
+    /// the new accumulator, returning an array of values to emit.
+    /// With `emit`, `fold` returns a stream of the emitted values
+    /// instead of the final accumulator.
+    pub emit: Option<Func>,
+    /// a function taking the final accumulator and returning
+    /// an array of values to emit at the end of the stream.
+    pub final_emit: Option<Func>,
+}
 
 #[derive(Debug, Clone, Serialize, Default, PartialEq, Eq, PartialOrd, Ord, CommandOptions)]
 pub struct GetAllOption {
@@ -221,6 +244,49 @@ pub struct GroupOption {
     pub multi: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Default, CommandOptions)]
+#[non_exhaustive]
+pub struct HttpOption {
+    /// the number of seconds before `http` times out (default 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    /// the number of retries to perform in case of failure (default 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<usize>,
+    /// the number of redirects to follow (default 1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirects: Option<usize>,
+    /// whether to verify the SSL certificate of the server (default `true`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify: Option<bool>,
+    /// how to interpret the body of the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_format: Option<ResultFormat>,
+    /// the HTTP method to use (default `HttpMethod::Get`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<HttpMethod>,
+    /// the authentication to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<HttpAuth>,
+    /// an object of URL parameters, appended to the URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    /// additional headers sent with the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<HashMap<Cow<'static, str>, Cow<'static, str>>>,
+    /// the body of the request. Objects are form-encoded for `POST`
+    /// requests and JSON-encoded otherwise, strings are sent as is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// how to find the next page, which makes `http` return a stream
+    /// of which [run](crate::Command::run) only returns the first batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<Page>,
+    /// the number of pages to fetch, `-1` for no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_limit: Option<isize>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
 pub struct IndexCreateOption {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -234,8 +300,7 @@ pub struct IndexRenameOption {
     pub overwrite: Option<bool>,
 }
 
-// TODO finish this struct
-#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
+#[derive(Debug, Clone, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
 #[non_exhaustive]
 pub struct InsertOption {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -244,12 +309,34 @@ pub struct InsertOption {
     pub return_changes: Option<ReturnChanges>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conflict: Option<Conflict>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub conflict_func: Command,
+    /// a function resolving conflicts, called with the `id`,
+    /// the old document and the new document. It returns the
+    /// document to store and takes precedence over `conflict`.
+    #[serde(skip)]
+    pub conflict_func: Option<Func>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ignore_write_hook: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, CommandOptions)]
+#[non_exhaustive]
+pub struct InsertSinkOption {
+    /// options of every `insert` query.
+    pub insert: InsertOption,
+    /// the maximum number of documents per batch. Defaults to 1000.
+    pub batch_size: Option<usize>,
+    /// the maximum size of a batch, in bytes of JSON. Defaults to 4 MiB.
+    pub batch_bytes: Option<usize>,
+    /// the number of `insert` queries running at once. Defaults to 4.
+    pub concurrency: Option<usize>,
+    /// the number of times a batch is retried after an
+    /// `OpFailed` availability error. Defaults to 3.
+    pub retries: Option<usize>,
+    /// the delay before the first retry, doubled after each one.
+    /// Defaults to 100 milliseconds.
+    pub retry_delay: Option<Duration>,
+}
+
 #[derive(Debug, Clone, Serialize, Default, PartialEq, PartialOrd, CommandOptions)]
 pub struct JsOption {
     /// `timeout` is the number of seconds before r.js times out.
@@ -447,12 +534,57 @@ pub struct RunOption {
 }
 
 impl RunOption {
+    /// Fill the options which are not set with the defaults of the
+    /// [session view](crate::Session::with_defaults), then with the
+    /// database of the session.
     pub async fn default_db(self, session: &Session) -> RunOption {
+        let options = self.or(&session.defaults);
+        if options.db.is_some() {
+            return options;
+        }
         let session_db = session.inner.db.lock().await;
-        if self.db.is_none() && *session_db != DEFAULT_RETHINKDB_DBNAME {
-            return self.db(&*session_db);
+        if *session_db != DEFAULT_RETHINKDB_DBNAME {
+            return options.db(&*session_db);
+        }
+        options
+    }
+
+    /// Fill the options which are not set with those of `defaults`.
+    pub fn or(self, defaults: &RunOption) -> RunOption {
+        let RunOption {
+            read_mode,
+            time_format,
+            profile,
+            durability,
+            group_format,
+            noreply,
+            db,
+            array_limit,
+            binary_format,
+            min_batch_rows,
+            max_batch_rows,
+            max_batch_bytes,
+            max_batch_seconds,
+            first_batch_scaledown_factor,
+        } = self;
+
+        RunOption {
+            read_mode: read_mode.or(defaults.read_mode),
+            time_format: time_format.or(defaults.time_format),
+            profile: profile.or(defaults.profile),
+            durability: durability.or(defaults.durability),
+            group_format: group_format.or(defaults.group_format),
+            noreply: noreply.or(defaults.noreply),
+            db: db.or_else(|| defaults.db.clone()),
+            array_limit: array_limit.or(defaults.array_limit),
+            binary_format: binary_format.or(defaults.binary_format),
+            min_batch_rows: min_batch_rows.or(defaults.min_batch_rows),
+            max_batch_rows: max_batch_rows.or(defaults.max_batch_rows),
+            max_batch_bytes: max_batch_bytes.or(defaults.max_batch_bytes),
+            max_batch_seconds: max_batch_seconds.or(defaults.max_batch_seconds),
+            first_batch_scaledown_factor: first_batch_scaledown_factor
+                .or(defaults.first_batch_scaledown_factor),
         }
-        self
     }
 }
 
@@ -537,11 +669,13 @@ pub struct UnionOption {
     /// The optional `interleave` argument controls
     /// how the sequences will be merged:
     /// - `Interleave::Bool(true)`: results will be mixed together;
-    /// this is the fastest setting, but ordering of elements is not guaranteed.
-    /// (This is the default.)
+    ///   this is the fastest setting, but ordering of elements is not guaranteed.
+    ///   (This is the default.)
     /// - `Interleave::Bool(false)`: input sequences will be appended to one another, left to right.
     /// - `Interleave::FieldName(field_name)`: a string will be taken as the name of a field
-    /// to perform a merge-sort on. The input sequences must be ordered **before** being passed to `union`.
+    ///   to perform a merge-sort on. The input sequences must be ordered **before** being passed to `union`.
+    /// - `Interleave::Func(func)`: a function taking a document and returning
+    ///   the value to merge-sort on, the sequences must be ordered the same way.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interleave: Option<Interleave>,
 }
@@ -570,3 +704,23 @@ pub struct WaitOption {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<f64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Durability, ReadMode, RunOption};
+
+    #[test]
+    fn test_run_option_or() {
+        let defaults = RunOption::default()
+            .db("tenant")
+            .read_mode(ReadMode::Majority)
+            .durability(Durability::Soft);
+        let options = RunOption::default()
+            .read_mode(ReadMode::Outdated)
+            .or(&defaults);
+
+        assert_eq!(options.read_mode, Some(ReadMode::Outdated));
+        assert_eq!(options.durability, Some(Durability::Soft));
+        assert_eq!(options.db, defaults.db);
+    }
+}
@@ -0,0 +1,114 @@
+//! Minimal CSV reader and writer following RFC 4180.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Text of a cell: strings are written as is, `null` as an
+/// empty cell and any other value as JSON.
+pub(super) fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.to_owned(),
+        Some(value) => value.to_string(),
+    }
+}
+
+pub(super) fn write_record<W: Write>(writer: &mut W, cells: &[String]) -> io::Result<()> {
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+
+        if cell.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", cell.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(cell.as_bytes())?;
+        }
+    }
+
+    writer.write_all(b"\n")
+}
+
+/// Read the next record, whose quoted cells may span several lines.
+pub(super) fn read_record<R: BufRead>(reader: &mut R) -> io::Result<Option<Vec<String>>> {
+    let mut line = String::new();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            if quoted {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "unterminated quoted CSV cell",
+                ));
+            }
+            if cells.is_empty() && cell.is_empty() {
+                return Ok(None);
+            }
+            cells.push(cell);
+            return Ok(Some(cells));
+        }
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                (true, '"') => quoted = false,
+                (true, c) => cell.push(c),
+                (false, '"') => quoted = true,
+                (false, ',') => cells.push(std::mem::take(&mut cell)),
+                (false, '\r' | '\n') => {}
+                (false, c) => cell.push(c),
+            }
+        }
+
+        if !quoted {
+            cells.push(cell);
+            return Ok(Some(cells));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{read_record, write_record};
+
+    #[test]
+    fn test_round_trip() {
+        let records = vec![
+            vec!["id".to_owned(), "title".to_owned()],
+            vec!["1".to_owned(), "plain".to_owned()],
+            vec!["2".to_owned(), "with, comma and \"quotes\"".to_owned()],
+            vec!["3".to_owned(), "multi\nline".to_owned()],
+            vec!["4".to_owned(), String::new()],
+        ];
+        let mut buffer = Vec::new();
+        for record in &records {
+            write_record(&mut buffer, record).unwrap();
+        }
+
+        let mut reader = Cursor::new(buffer);
+        let mut read = Vec::new();
+        while let Some(record) = read_record(&mut reader).unwrap() {
+            read.push(record);
+        }
+
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let mut reader = Cursor::new("1,\"open\n");
+
+        assert!(read_record(&mut reader).is_err());
+    }
+}
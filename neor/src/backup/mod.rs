@@ -0,0 +1,375 @@
+//! Dump and restore tables, like the `rethinkdb dump` and
+//! `rethinkdb restore` tools.
+//!
+//! A table is dumped as its documents, in [JSON Lines](https://jsonlines.org)
+//! or CSV, and a metadata file holding its configuration, the functions of
+//! its secondary indexes and its write hook. Restoring recreates the table
+//! with the same primary key and indexes before inserting the documents,
+//! then sets the write hook, which would otherwise change the documents
+//! as they are restored.
+//!
+//! CSV only keeps strings: every cell is restored as a string, and empty
+//! cells are skipped. Prefer JSON Lines for backups.
+//!
+//! ```
+//! use neor::backup::{self, DumpOption, RestoreOption};
+//! use neor::{r, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     let dumped = backup::dump(&conn, "test", "posts", "backup", &DumpOption::default()).await?;
+//!
+//!     r.db("test").table_drop("posts").run(&conn).await?;
+//!
+//!     let restored =
+//!         backup::restore(&conn, "test", "posts", "backup", &RestoreOption::default()).await?;
+//!
+//!     assert!(dumped == restored);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The same operations are available from the command line
+//! with the `neor-backup` binary.
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use futures::TryStreamExt;
+use neor_macros::CommandOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::admin::TableConfig;
+use crate::arguments::{Durability, IndexCreateOption, InsertOption, TableCreateOption};
+use crate::err::ReqlDriverError;
+use crate::migrate::{self, list_contains};
+use crate::types::{GetWriteHookResponse, IndexStatusResponse, MutationResponse};
+use crate::{args, r, Command, Converter, Result, Session};
+
+mod csv;
+
+/// Number of documents inserted per query by default
+pub const DEFAULT_CHUNK_SIZE: usize = 200;
+
+/// The file extension of the metadata files
+pub const METADATA_EXTENSION: &str = "info";
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// one JSON document per line.
+    #[default]
+    JsonLines,
+    /// a header with the field names, then one document per line.
+    Csv,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Everything needed to recreate a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TableMetadata {
+    pub config: TableConfig,
+    pub indexes: Vec<IndexStatusResponse>,
+    pub write_hook: Option<GetWriteHookResponse>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, CommandOptions)]
+#[non_exhaustive]
+pub struct DumpOption {
+    pub format: Option<Format>,
+    /// the columns of a CSV dump, the other fields are not dumped.
+    /// By default the fields of the first document, and the dump
+    /// fails when a later document has another field.
+    pub fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, CommandOptions)]
+#[non_exhaustive]
+pub struct RestoreOption {
+    pub format: Option<Format>,
+    /// number of documents per insert, 200 by default.
+    pub chunk_size: Option<usize>,
+    /// durability of the inserts, soft by default.
+    pub durability: Option<Durability>,
+}
+
+/// Read the configuration, index functions and write hook of a table.
+pub async fn table_metadata(session: &Session, db: &str, table: &str) -> Result<TableMetadata> {
+    let query = r.db(db).table(table);
+
+    Ok(TableMetadata {
+        config: parse(query.config().run(session).await?)?,
+        indexes: parse(query.index_status(()).run(session).await?)?,
+        write_hook: parse(query.get_write_hook().run(session).await?)?,
+    })
+}
+
+/// Stream the documents of a table to `writer`,
+/// returning the number of documents written.
+pub async fn dump_table<W: Write>(
+    session: &Session,
+    db: &str,
+    table: &str,
+    options: &DumpOption,
+    mut writer: W,
+) -> Result<u64> {
+    let format = options.format.unwrap_or_default();
+    let mut fields = options.fields.clone();
+    // a header read from the first document must hold every field
+    let strict = fields.is_none();
+    let mut query = r.db(db).table(table).build_query(session);
+    let mut count = 0;
+
+    if let (Format::Csv, Some(fields)) = (format, &fields) {
+        csv::write_record(&mut writer, fields)?;
+    }
+
+    while let Some(batch) = query.try_next().await? {
+        for document in rows(batch) {
+            match format {
+                Format::JsonLines => {
+                    serde_json::to_writer(&mut writer, &document)?;
+                    writer.write_all(b"\n")?;
+                }
+                Format::Csv => {
+                    let fields = match &fields {
+                        Some(fields) => fields,
+                        None => {
+                            let mut keys: Vec<String> = match &document {
+                                Value::Object(document) => document.keys().cloned().collect(),
+                                _ => Vec::new(),
+                            };
+                            keys.sort_unstable();
+                            csv::write_record(&mut writer, &keys)?;
+                            fields.insert(keys)
+                        }
+                    };
+                    if let (true, Value::Object(document)) = (strict, &document) {
+                        if let Some(key) = document.keys().find(|key| !fields.contains(key)) {
+                            return Err(ReqlDriverError::Other(format!(
+                                "the field `{}` of document {} is not in the CSV header, \
+                                 set the fields of the dump",
+                                key,
+                                count + 1
+                            ))
+                            .into());
+                        }
+                    }
+                    let cells: Vec<String> = fields
+                        .iter()
+                        .map(|field| csv::cell(document.get(field)))
+                        .collect();
+                    csv::write_record(&mut writer, &cells)?;
+                }
+            }
+            count += 1;
+        }
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Recreate a table from its metadata, insert the documents read
+/// from `reader`, then set its write hook, returning the number of
+/// documents inserted.
+///
+/// The database, table and indexes are only created
+/// when they do not exist.
+pub async fn restore_table<R: BufRead>(
+    session: &Session,
+    db: &str,
+    table: &str,
+    metadata: &TableMetadata,
+    options: &RestoreOption,
+    mut reader: R,
+) -> Result<u64> {
+    let query = r.db(db).table(table);
+    let table_options = TableCreateOption::default()
+        .primary_key(&metadata.config.primary_key)
+        .durability(metadata.config.durability);
+
+    migrate::ensure_db(session, db).await?;
+    migrate::ensure_table(session, db, table, table_options).await?;
+
+    for index in &metadata.indexes {
+        if list_contains(session, query.index_list(), &index.index).await? {
+            continue;
+        }
+        let index_options = IndexCreateOption::default()
+            .multi(index.multi)
+            .geo(index.geo);
+        query
+            .index_create(args!(
+                index.index.as_ref(),
+                index.function.clone(),
+                index_options
+            ))
+            .run(session)
+            .await?;
+    }
+    query.index_wait(()).run(session).await?;
+
+    let chunk_size = options.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+    let insert_options =
+        InsertOption::default().durability(options.durability.unwrap_or(Durability::Soft));
+    let mut chunk = Vec::with_capacity(chunk_size);
+    let mut count = 0;
+
+    let csv_fields = match options.format.unwrap_or_default() {
+        Format::JsonLines => None,
+        Format::Csv => Some(csv::read_record(&mut reader)?.unwrap_or_default()),
+    };
+
+    while let Some(document) = next_document(&mut reader, csv_fields.as_deref())? {
+        chunk.push(document);
+
+        if chunk.len() == chunk_size {
+            count += insert(session, &query, &mut chunk, &insert_options).await?;
+        }
+    }
+    if !chunk.is_empty() {
+        count += insert(session, &query, &mut chunk, &insert_options).await?;
+    }
+
+    // set last, so that the hook does not rewrite the restored documents
+    if let Some(write_hook) = &metadata.write_hook {
+        query
+            .set_write_hook(write_hook.function.clone())
+            .run(session)
+            .await?;
+    }
+
+    Ok(count)
+}
+
+/// Dump a table to `<dir>/<db>/<table>.<format>` along with its
+/// metadata in `<dir>/<db>/<table>.info`.
+pub async fn dump(
+    session: &Session,
+    db: &str,
+    table: &str,
+    dir: impl AsRef<Path>,
+    options: &DumpOption,
+) -> Result<u64> {
+    let (data_path, metadata_path) = paths(dir.as_ref(), db, table, options.format);
+    fs::create_dir_all(dir.as_ref().join(db))?;
+
+    let metadata = table_metadata(session, db, table).await?;
+    serde_json::to_writer_pretty(BufWriter::new(File::create(metadata_path)?), &metadata)?;
+
+    let writer = BufWriter::new(File::create(data_path)?);
+    dump_table(session, db, table, options, writer).await
+}
+
+/// Restore a table dumped by [dump].
+pub async fn restore(
+    session: &Session,
+    db: &str,
+    table: &str,
+    dir: impl AsRef<Path>,
+    options: &RestoreOption,
+) -> Result<u64> {
+    let (data_path, metadata_path) = paths(dir.as_ref(), db, table, options.format);
+
+    let metadata: TableMetadata =
+        serde_json::from_reader(BufReader::new(File::open(metadata_path)?))?;
+    let reader = BufReader::new(File::open(data_path)?);
+
+    restore_table(session, db, table, &metadata, options, reader).await
+}
+
+fn paths(dir: &Path, db: &str, table: &str, format: Option<Format>) -> (PathBuf, PathBuf) {
+    let base = dir.join(db).join(table);
+    let format = format.unwrap_or_default();
+
+    (
+        base.with_extension(format.extension()),
+        base.with_extension(METADATA_EXTENSION),
+    )
+}
+
+/// Read the next document, from a CSV record when the fields
+/// of the header are given or from a JSON line otherwise.
+fn next_document<R: BufRead>(
+    reader: &mut R,
+    csv_fields: Option<&[String]>,
+) -> Result<Option<Value>> {
+    if let Some(fields) = csv_fields {
+        while let Some(cells) = csv::read_record(reader)? {
+            let document: Map<String, Value> = fields
+                .iter()
+                .zip(cells)
+                .filter(|(_, cell)| !cell.is_empty())
+                .map(|(field, cell)| (field.to_owned(), Value::String(cell)))
+                .collect();
+            if !document.is_empty() {
+                return Ok(Some(Value::Object(document)));
+            }
+        }
+        return Ok(None);
+    }
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            return Ok(Some(serde_json::from_str(&line)?));
+        }
+    }
+}
+
+async fn insert(
+    session: &Session,
+    query: &Command,
+    chunk: &mut Vec<Value>,
+    options: &InsertOption,
+) -> Result<u64> {
+    let documents = std::mem::take(chunk);
+    let response: MutationResponse = parse(
+        query
+            .insert(args!(documents, options.clone()))
+            .run(session)
+            .await?,
+    )?;
+
+    if response.errors > 0 {
+        return Err(ReqlDriverError::Other(format!(
+            "failed to restore {} document(s): {}",
+            response.errors,
+            response.first_error.unwrap_or_default()
+        ))
+        .into());
+    }
+
+    Ok(response.inserted as u64)
+}
+
+fn rows(batch: Value) -> Vec<Value> {
+    match batch {
+        Value::Array(rows) => rows,
+        row => vec![row],
+    }
+}
+
+fn parse<T>(value: Option<Value>) -> Result<T>
+where
+    T: Serialize + serde::de::DeserializeOwned + Unpin,
+{
+    value.unwrap_or_default().parse()
+}
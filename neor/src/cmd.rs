@@ -5,6 +5,7 @@ pub mod args;
 pub mod array;
 pub mod asc;
 pub mod avg;
+pub mod batcher;
 pub mod between;
 pub mod binary;
 pub mod bit_and;
@@ -83,6 +84,7 @@ pub mod info;
 pub mod inner_join;
 pub mod insert;
 pub mod insert_at;
+pub mod insert_sink;
 pub mod intersects;
 pub mod is_empty;
 pub mod iso8601;
@@ -111,6 +113,7 @@ pub mod offsets_of;
 pub mod or;
 pub mod order_by;
 pub mod outer_join;
+pub mod paginate;
 pub mod pluck;
 pub mod point;
 pub mod polygon;
@@ -123,6 +126,7 @@ pub mod reconfigure;
 pub mod reduce;
 pub mod rem;
 pub mod replace;
+pub mod revoke;
 pub mod round;
 pub mod run;
 pub mod sample;
@@ -156,6 +160,7 @@ pub mod ungroup;
 pub mod union;
 pub mod upcase;
 pub mod update;
+pub mod update_if;
 pub mod uuid;
 pub mod values;
 pub mod wait;
@@ -170,10 +175,11 @@ use std::str;
 use ::time::UtcOffset;
 use futures::stream::Stream;
 use futures::TryStreamExt;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::arguments::{Permission, ReconfigureOption};
-use crate::{Command, CommandArg, Func, Result};
+use crate::arguments::{BatcherOption, InsertSinkOption, Permission, ReconfigureOption};
+use crate::{Command, CommandArg, IntoFunc, Result, Session};
 
 impl<'a> Command {
     /// Turn a query into a changefeed, an infinite stream of objects
@@ -610,7 +616,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - index_name: `impl Into<String>` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc) | [Binary](crate::types::Binary)
     /// - options: [IndexCreateOption](crate::arguments::IndexCreateOption)
     /// - response: [IndexResponse](crate::types::IndexResponse)
     ///
@@ -751,7 +757,7 @@ impl<'a> Command {
     /// - [index_status](Self::index_status)
     /// - [index_list](Self::index_list)
     /// - [index_drop](Self::index_drop)
-    pub fn index_create(&self, args: impl index_create::IndexCreateArg) -> Self {
+    pub fn index_create<A>(&self, args: impl index_create::IndexCreateArg<A>) -> Self {
         index_create::new(args).with_parent(self)
     }
 
@@ -1353,6 +1359,37 @@ impl<'a> Command {
     ///
     /// ## Examples
     ///
+    /// Insert a document, adding up the `visits` of the
+    /// existing document when there is a conflict.
+    ///
+    /// ```
+    /// use neor::arguments::InsertOption;
+    /// use neor::types::MutationResponse;
+    /// use neor::{args, func, r, Converter, Result};
+    /// use serde_json::json;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let insert_option = InsertOption::default().conflict_func(func!(
+    ///         |_id, old_doc, new_doc| old_doc.merge(
+    ///             r.object([r.expr("visits"), old_doc.g("visits") + new_doc.g("visits")])
+    ///         )
+    ///     ));
+    ///     let conn = r.connection().connect().await?;
+    ///     let response: MutationResponse = r.table("users")
+    ///         .insert(args!(json!({"id": "malika", "visits": 1}), insert_option))
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.inserted + response.replaced == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Examples
+    ///
     /// Copy the documents from `posts` to `posts_backup`.
     ///
     /// ```
@@ -1412,6 +1449,73 @@ impl<'a> Command {
         insert::new(args).with_parent(self)
     }
 
+    /// Insert documents in batches through a [Sink](futures::Sink).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// table.insert_sink(&session, options) → sink
+    /// ```
+    ///
+    /// Where:
+    /// - session: [Session](crate::Session)
+    /// - options: [InsertSinkOption](crate::arguments::InsertSinkOption)
+    /// - sink: [InsertSink](crate::cmd::insert_sink::InsertSink)
+    ///
+    /// # Description
+    ///
+    /// The sink accepts documents one by one and inserts them with
+    /// several concurrent `insert` queries, each holding a batch limited
+    /// in number of documents and in bytes. This avoids building huge
+    /// arrays when loading many documents. Batches failing with an
+    /// `OpFailed` availability error are retried.
+    ///
+    /// Once the sink is closed, its response sums the responses of
+    /// every batch and lists the first error of each failed batch.
+    ///
+    /// ## Examples
+    ///
+    /// Insert a stream of documents into the table `posts`,
+    /// at most 500 per query.
+    ///
+    /// ```
+    /// use futures::{stream, SinkExt, StreamExt};
+    /// use neor::arguments::{Durability, InsertOption, InsertSinkOption};
+    /// use neor::{r, Result};
+    /// use serde_json::json;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let insert_option = InsertOption::default().durability(Durability::Soft);
+    ///     let options = InsertSinkOption::default()
+    ///         .insert(insert_option)
+    ///         .batch_size(500);
+    ///     let mut sink = r.table("posts").insert_sink(&conn, options);
+    ///     let mut posts = stream::iter(0..10_000)
+    ///         .map(|id| Ok(json!({"id": id, "title": "Lorem ipsum"})));
+    ///
+    ///     sink.send_all(&mut posts).await?;
+    ///     sink.close().await?;
+    ///
+    ///     let response = sink.into_response();
+    ///
+    ///     assert!(response.mutation.inserted == 10_000);
+    ///     assert!(response.batches == 20);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [insert](Self::insert)
+    pub fn insert_sink<T>(
+        &self,
+        session: &Session,
+        options: InsertSinkOption,
+    ) -> insert_sink::InsertSink<T> {
+        insert_sink::new(self, session, options)
+    }
+
     /// Update JSON documents in a table.
     ///
     /// # Command syntax
@@ -1722,6 +1826,143 @@ impl<'a> Command {
         update::new(args).with_parent(self)
     }
 
+    /// Update a document only if its version is the expected one.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// table.get(id).update_if_version(expected, patch) → response
+    /// ```
+    ///
+    /// Where:
+    /// - expected: `impl Serialize` | [Command](crate::Command)
+    /// - patch: `impl Serialize` | [Command](crate::Command)
+    /// - response: [MutationResponse](crate::types::MutationResponse)
+    ///
+    /// # Description
+    ///
+    /// An optimistic concurrency control: the document is atomically
+    /// checked and updated on the server. When its `version` field
+    /// (0 when missing) equals `expected`, the patch is applied and
+    /// the version is incremented. Otherwise the document is left
+    /// untouched and the query fails with a
+    /// [Conflict](crate::err::ReqlRuntimeError::Conflict) error, so
+    /// that the caller can read the document again and retry.
+    ///
+    /// ## Note
+    ///
+    /// Only a single document, selected with `get`, can be updated: the
+    /// query fails without being sent on any other selection.
+    ///
+    /// ## Examples
+    ///
+    /// Publish the post with `id` of `1` unless it was
+    /// modified since its version `3` was read.
+    ///
+    /// ```
+    /// use neor::err::{ReqlError, ReqlRuntimeError};
+    /// use neor::types::MutationResponse;
+    /// use neor::{r, Converter, Result};
+    /// use serde_json::json;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let result = r.table("posts")
+    ///         .get(1)
+    ///         .update_if_version(3, json!({"status": "published"}))
+    ///         .run(&conn)
+    ///         .await;
+    ///
+    ///     match result {
+    ///         Ok(response) => {
+    ///             let response: MutationResponse = response.unwrap().parse()?;
+    ///             assert!(response.replaced == 1);
+    ///         }
+    ///         Err(ReqlError::Runtime(ReqlRuntimeError::Conflict(_))) => {
+    ///             // read the post again and retry
+    ///         }
+    ///         Err(error) => return Err(error),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [update_if](Self::update_if)
+    /// - [update](Self::update)
+    pub fn update_if_version(
+        &self,
+        expected: impl Into<CommandArg>,
+        patch: impl Into<CommandArg>,
+    ) -> Self {
+        update_if::new_versioned(self, expected, patch)
+    }
+
+    /// Update a document only if it satisfies a predicate.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// table.get(id).update_if(predicate, patch) → response
+    /// ```
+    ///
+    /// Where:
+    /// - predicate: [Func](crate::Func) | [closure](crate::IntoFunc)
+    /// - patch: `impl Serialize` | [Command](crate::Command)
+    /// - response: [MutationResponse](crate::types::MutationResponse)
+    ///
+    /// # Description
+    ///
+    /// The generic form of [update_if_version](Self::update_if_version):
+    /// the patch is applied when the predicate, called with the current
+    /// document, returns `true`. Otherwise the query fails with a
+    /// [Conflict](crate::err::ReqlRuntimeError::Conflict) error.
+    ///
+    /// ## Note
+    ///
+    /// Only a single document, selected with `get`, can be updated: the
+    /// query fails without being sent on any other selection.
+    ///
+    /// ## Examples
+    ///
+    /// Rename a user only if its email did not change.
+    ///
+    /// ```
+    /// use neor::types::MutationResponse;
+    /// use neor::{r, Command, Converter, Result};
+    /// use serde_json::json;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response: MutationResponse = r.table("users")
+    ///         .get("malika")
+    ///         .update_if(
+    ///             |user: Command| user.g("email").eq("malika@rethinkdb.com"),
+    ///             json!({"name": "Malika"}),
+    ///         )
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.replaced == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [update_if_version](Self::update_if_version)
+    /// - [update](Self::update)
+    pub fn update_if(
+        &self,
+        predicate: impl IntoFunc<(Command,)>,
+        patch: impl Into<CommandArg>,
+    ) -> Self {
+        update_if::new(self, predicate, patch)
+    }
+
     /// Replace documents in a table.
     ///
     /// # Command syntax
@@ -2266,6 +2507,82 @@ impl<'a> Command {
         get_all::new(values).with_parent(self)
     }
 
+    /// Load documents by key, running one `get_all` query
+    /// for the keys loaded at the same time.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// table.batcher(&session, options) → batcher
+    /// ```
+    ///
+    /// Where:
+    /// - session: [Session](crate::Session)
+    /// - options: [BatcherOption](crate::arguments::BatcherOption)
+    /// - batcher: [Batcher](crate::cmd::batcher::Batcher)
+    ///
+    /// # Description
+    ///
+    /// Each call to `load` waits a short `delay` for other keys, then
+    /// the waiting keys are read by a single query and every caller
+    /// receives the documents of its own key. A query holds at most
+    /// `max_batch_size` keys, and is run as soon as it is full.
+    ///
+    /// The documents are kept by the batcher, so a key is only loaded
+    /// once. Create a batcher per request, or disable the `cache`
+    /// when the batcher lives longer.
+    ///
+    /// ## Examples
+    ///
+    /// Load the authors of several posts with one query.
+    ///
+    /// ```
+    /// use futures::future;
+    /// use neor::arguments::BatcherOption;
+    /// use neor::{r, Result};
+    /// use serde_json::Value;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let authors = r.table("authors").batcher(&conn, BatcherOption::default());
+    ///
+    ///     let loads = ["william", "lara", "william"].map(|id| authors.load::<Value>(id));
+    ///     let authors = future::try_join_all(loads).await?;
+    ///
+    ///     assert!(authors.len() == 3);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Load the posts of an author, with the secondary index `author_id`.
+    ///
+    /// ```
+    /// use neor::arguments::{BatcherOption, GetAllOption};
+    /// use neor::{r, Result};
+    /// use serde_json::Value;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let options = BatcherOption::default()
+    ///         .get_all(GetAllOption::default().index("author_id"))
+    ///         .max_batch_size(50);
+    ///     let posts = r.table("posts").batcher(&conn, options);
+    ///
+    ///     let response: Vec<Value> = posts.load_many("william").await?;
+    ///
+    ///     assert!(response.iter().all(|post| post["author_id"] == "william"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [get_all](Self::get_all)
+    pub fn batcher(&self, session: &Session, options: BatcherOption) -> batcher::Batcher {
+        batcher::new(self, session, options)
+    }
+
     /// Get all documents between two keys.
     ///
     /// # Command syntax
@@ -2438,6 +2755,102 @@ impl<'a> Command {
         between::new(args).with_parent(self)
     }
 
+    /// Read a table page by page, ordered by an index.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// table.paginate(index, page_size, after) → paginate
+    /// ```
+    ///
+    /// Where:
+    /// - index: &'static str, String, [PageIndex](crate::cmd::paginate::PageIndex)
+    /// - page_size: usize
+    /// - after: Option<[Cursor](crate::cmd::paginate::Cursor)>
+    /// - paginate: [Paginate](crate::cmd::paginate::Paginate)
+    ///
+    /// # Description
+    ///
+    /// Each page returns a cursor holding the key of its last document,
+    /// and the next page reads the documents after this key with
+    /// `between`, `order_by` and `limit`. Unlike `skip`, reading a page
+    /// does not read the pages before it.
+    ///
+    /// The cursor is turned into an opaque token with `to_string`, to
+    /// be sent to a client, and read back with `parse`. The last page
+    /// has no cursor.
+    ///
+    /// ## Note
+    ///
+    /// The key of a document is read from its fields, and must be unique
+    /// so that no document is skipped between two pages: paginate with
+    /// the primary key, or a compound index ending with it. The query
+    /// fails with any other index.
+    ///
+    /// ## Examples
+    ///
+    /// Read the posts 20 at a time, by primary key.
+    ///
+    /// ```
+    /// use neor::cmd::paginate::Cursor;
+    /// use neor::{r, Result};
+    /// use serde_json::Value;
+    ///
+    /// async fn example(token: Option<&str>) -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let after = token.map(str::parse::<Cursor>).transpose()?;
+    ///     let page = r.table("posts").paginate("id", 20, after).run::<Value>(&conn).await?;
+    ///
+    ///     assert!(page.items.len() <= 20);
+    ///
+    ///     let next_token = page.next.map(|cursor| cursor.to_string());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Read the latest posts first, with the compound index `date_id`
+    /// on the fields `date` and `id`.
+    ///
+    /// ```
+    /// use neor::cmd::paginate::PageIndex;
+    /// use neor::{r, Result};
+    /// use serde_json::Value;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let index = PageIndex::compound("date_id", ["date", "id"]).desc();
+    ///     let mut after = None;
+    ///
+    ///     loop {
+    ///         let page = r.table("posts")
+    ///             .paginate(index.clone(), 100, after)
+    ///             .run::<Value>(&conn)
+    ///             .await?;
+    ///
+    ///         match page.next {
+    ///             Some(cursor) => after = Some(cursor),
+    ///             None => break,
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [between](Self::between)
+    /// - [order_by](Self::order_by)
+    /// - [limit](Self::limit)
+    pub fn paginate(
+        &self,
+        index: impl Into<paginate::PageIndex>,
+        page_size: usize,
+        after: Option<paginate::Cursor>,
+    ) -> paginate::Paginate {
+        paginate::new(self, index, page_size, after)
+    }
+
     /// Return all the elements in a sequence for which the given predicate is true.
     ///
     /// # Command syntax
@@ -2451,7 +2864,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - predicate: `impl Serialize` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - options: [FilterOption](crate::arguments::FilterOption)
     ///
     /// # Description
@@ -2616,6 +3029,27 @@ impl<'a> Command {
     /// }
     /// ```
     ///
+    /// ## Examples
+    ///
+    /// The predicate can also be a plain closure,
+    /// its argument must be annotated with `Command`.
+    ///
+    /// ```
+    /// use neor::{r, Command, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(|user: Command| user.g("age").ge(18))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
     /// # More complex predicates
     ///
     /// ## Examples
@@ -2790,7 +3224,7 @@ impl<'a> Command {
     /// - [get](Self::get)
     /// - [get_all](Self::get_all)
     /// - [between](Self::between)
-    pub fn filter(&self, args: impl filter::FilterArg) -> Self {
+    pub fn filter<A>(&self, args: impl filter::FilterArg<A>) -> Self {
         filter::new(args).with_parent(self)
     }
 
@@ -2804,7 +3238,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - other_sequence: [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - response: [Vec<JoinResponse<Left, Right>>](crate::types::JoinResponse)
     ///
     /// # Description
@@ -2852,7 +3286,11 @@ impl<'a> Command {
     /// - [eq_join](Self::eq_join)
     /// - [outer_join](Self::outer_join)
     /// - [zip](Self::zip)
-    pub fn inner_join(&self, other_sequence: Command, func: Func) -> Self {
+    pub fn inner_join(
+        &self,
+        other_sequence: Command,
+        func: impl IntoFunc<(Command, Command)>,
+    ) -> Self {
         inner_join::new(other_sequence, func).with_parent(self)
     }
 
@@ -2866,7 +3304,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - other_sequence: [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - response: [Vec<JoinResponse<Left, Right>>](crate::types::JoinResponse)
     ///
     /// # Description
@@ -2914,7 +3352,11 @@ impl<'a> Command {
     /// - [eq_join](Self::eq_join)
     /// - [inner_join](Self::inner_join)
     /// - [zip](Self::zip)
-    pub fn outer_join(&self, other_sequence: Command, func: Func) -> Self {
+    pub fn outer_join(
+        &self,
+        other_sequence: Command,
+        func: impl IntoFunc<(Command, Command)>,
+    ) -> Self {
         outer_join::new(other_sequence, func).with_parent(self)
     }
 
@@ -3119,9 +3561,9 @@ impl<'a> Command {
     /// ```
     ///
     /// Where:
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
-    /// - sequences: `impl IntoIterator<Item = Command>`
+    /// - sequences: `impl IntoIterator<Item = Command>`, or `[Command; N]` with a closure
     ///
     /// # Description
     ///
@@ -3321,7 +3763,7 @@ impl<'a> Command {
     /// ```
     ///
     /// Where:
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     ///
     /// # Description
     ///
@@ -3389,7 +3831,7 @@ impl<'a> Command {
     /// # Related commands
     /// - [map](Self::map)
     /// - [reduce](Self::reduce)
-    pub fn concat_map(&self, func: Func) -> Command {
+    pub fn concat_map(&self, func: impl IntoFunc<(Command,)>) -> Command {
         concat_map::new(func).with_parent(self)
     }
 
@@ -3406,7 +3848,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - index: [index](crate::r::index)
-    /// - predicate: `impl Into<String>` | [Func](crate::Func) | [Command](crate::Command)
+    /// - predicate: `impl Into<String>` | [Func](crate::Func) | [closure](crate::IntoFunc) | [Command](crate::Command)
     /// - predicates: `[predicate; N]`
     ///
     /// # Description
@@ -3657,7 +4099,7 @@ impl<'a> Command {
     /// - [skip](Self::skip)
     /// - [limit](Self::limit)
     /// - [slice](Self::slice)
-    pub fn order_by(&self, args: impl order_by::OrderByArg) -> Self {
+    pub fn order_by<A>(&self, args: impl order_by::OrderByArg<A>) -> Self {
         order_by::new(args).with_parent(self)
     }
 
@@ -4232,7 +4674,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - field: `&str | [&str; N]`
-    /// - func: [Func](crate::Func) | [Func; N]
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc) | [Func; N]
     /// - grouped_stream: [GroupedStream](crate::types::GroupedStream)
     /// - sequence: [Command](crate::Command)
     ///
@@ -4443,7 +4885,7 @@ impl<'a> Command {
     /// ```
     ///
     /// Where:
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -4526,7 +4968,7 @@ impl<'a> Command {
     /// - [avg](Self::avg)
     /// - [min](Self::min)
     /// - [max](Self::max)
-    pub fn reduce(&self, func: Func) -> Self {
+    pub fn reduce(&self, func: impl IntoFunc<(Command, Command)>) -> Self {
         reduce::new(func).with_parent(self)
     }
 
@@ -4537,11 +4979,13 @@ impl<'a> Command {
     ///
     /// ```text
     /// sequence.fold(base, func) → value
+    /// sequence.fold(base, args!(func, options)) → sequence
     /// ```
     ///
     /// Where:
     /// - base: `impl Serialize` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
+    /// - options: [FoldOption](crate::arguments::FoldOption)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -4592,11 +5036,33 @@ impl<'a> Command {
     /// a RethinkDB table or other stream, which is
     /// not guaranteed with `reduce`.)
     ///
+    /// Return every intermediate result with `emit`.
+    ///
+    /// ```
+    /// use neor::arguments::FoldOption;
+    /// use neor::{args, func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let fold_option = FoldOption::default()
+    ///         .emit(func!(|_acc, _row, new_acc| r.array([new_acc])));
+    ///     let response = r.table("words")
+    ///         .order_by("id")
+    ///         .fold(0, args!(func!(|acc, _word| acc + 1), fold_option))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
     /// # Related commands
     /// - [reduce](Self::reduce)
     /// - [concat_map](Self::concat_map)
-    pub fn fold(&self, base: impl Into<CommandArg>, func: Func) -> Self {
-        fold::new(base, func).with_parent(self)
+    pub fn fold(&self, base: impl Into<CommandArg>, args: impl fold::FoldArg) -> Self {
+        fold::new(base, args).with_parent(self)
     }
 
     /// Count the number of elements in sequence or key/value pairs in an object,
@@ -4618,7 +5084,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - value: `impl Serialize`
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence, binary, string, object, query_cmd: [Command](crate::Command)
     ///
     /// # Description
@@ -4763,7 +5229,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -4865,7 +5331,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -4970,7 +5436,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - options: [MinOption](crate::arguments::MinOption)
     /// - sequence: [Command](crate::Command)
     ///
@@ -5128,7 +5594,7 @@ impl<'a> Command {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - options: [MaxOption](crate::arguments::MaxOption)
     /// - sequence: [Command](crate::Command)
     ///
@@ -8731,7 +9197,7 @@ impl<'a> Command {
     /// async fn example() -> Result<()> {
     ///     let conn = r.connection().connect().await?;
     ///     let response = r.table("users")
-    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.expr(11))))
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.november())))
     ///         .run(&conn)
     ///         .await?;
     ///
@@ -8825,7 +9291,7 @@ impl<'a> Command {
     /// async fn example() -> Result<()> {
     ///     let conn = r.connection().connect().await?;
     ///     let response = r.table("users")
-    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.expr(2))))
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.tuesday())))
     ///         .run(&conn)
     ///         .await?;
     ///
@@ -9326,7 +9792,7 @@ impl<'a> Command {
     /// ```
     ///
     /// Where:
-    /// - write_function: [Func](crate::Func)
+    /// - write_function: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - response: [MutationResponse](crate::types::MutationResponse)
     ///
     /// ## Examples
@@ -9358,7 +9824,7 @@ impl<'a> Command {
     ///
     /// # Related commands
     /// - [map](Self::map)
-    pub fn for_each(&self, write_function: Func) -> Self {
+    pub fn for_each(&self, write_function: impl IntoFunc<(Command,)>) -> Self {
         for_each::new(write_function).with_parent(self)
     }
 
@@ -10274,6 +10740,57 @@ impl<'a> Command {
         grant::new(username, permission).with_parent(self)
     }
 
+    /// Revoke all the permissions of a user account on a database or table,
+    /// so that they are inherited again from the enclosing scope.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// db.revoke(username) → response
+    /// table.revoke(username) → response
+    /// ```
+    ///
+    /// Where:
+    /// - username: `impl Into<String>`
+    /// - response: [GrantResponse](crate::types::GrantResponse)
+    ///
+    /// # Description
+    ///
+    /// This is [grant](Self::grant) with the `read`, `write` and `config`
+    /// permissions set to `null`.
+    ///
+    /// ## Examples
+    ///
+    /// Remove the permissions of `alima` on the `simbad` table.
+    ///
+    /// ```
+    /// use neor::types::GrantResponse;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let response: GrantResponse = r.db("users")
+    ///         .table("simbad")
+    ///         .revoke("alima")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.granted == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [grant](Self::grant)
+    /// - [permissions_for](crate::r::permissions_for)
+    pub fn revoke(&self, username: impl Into<String>) -> Self {
+        revoke::new(username, false).with_parent(self)
+    }
+
     /// Query (read and/or update) the configurations for individual tables or databases.
     ///
     /// # Command syntax
@@ -10881,6 +11398,104 @@ impl<'a> Command {
     pub fn build_query(&self, args: impl run::RunArg) -> impl Stream<Item = Result<Value>> {
         Box::pin(run::new(self.clone(), args))
     }
+
+    /// Run a query, deserializing its result straight from the response
+    ///
+    /// See [run](self::run) for more information.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// query.run_as::<T>(&session) → Option<T>
+    /// query.run_as::<T>(args!(&session, options)) → Option<T>
+    /// ```
+    ///
+    /// Where:
+    /// - T: `impl DeserializeOwned`
+    ///
+    /// # Description
+    ///
+    /// `run` reads the response into a [Value](serde_json::Value), which
+    /// is then [parsed](crate::Converter::parse). `run_as` skips the
+    /// `Value` and deserializes `T` from the JSON sent by the server.
+    /// With `T` a `Box<RawValue>` of `serde_json`, the JSON of the
+    /// result is returned as is, to be forwarded without being parsed.
+    ///
+    /// ## Examples
+    ///
+    /// Get the users as they are sent by the server.
+    ///
+    /// ```
+    /// use neor::{r, Result};
+    /// use serde_json::value::RawValue;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let users = r
+    ///         .db("rethinkdb")
+    ///         .table("users")
+    ///         .run_as::<Box<RawValue>>(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(users.unwrap().get().starts_with('['));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [run](self::run)
+    /// - [build_query_as](self::build_query_as)
+    pub async fn run_as<T>(&self, args: impl run::RunArg) -> Result<Option<T>>
+    where
+        T: Unpin + DeserializeOwned,
+    {
+        self.build_query_as(args).try_next().await
+    }
+
+    /// Prepare query for execution, deserializing its results straight
+    /// from the responses
+    ///
+    /// See [build_query](self::build_query) and [run_as](self::run_as)
+    /// for more information.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// query.build_query_as::<T>(&session) → stream
+    /// query.build_query_as::<T>(args!(&session, options)) → stream
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Forward each batch of a table to a client, without parsing it.
+    ///
+    /// ```
+    /// use futures::TryStreamExt;
+    /// use neor::{r, Result};
+    /// use serde_json::value::RawValue;
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let mut batches = r.table("simbad").build_query_as::<Box<RawValue>>(&conn);
+    ///
+    ///     while let Some(batch) = batches.try_next().await? {
+    ///         println!("{}", batch.get());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [build_query](self::build_query)
+    /// - [run_as](self::run_as)
+    pub fn build_query_as<T>(&self, args: impl run::RunArg) -> impl Stream<Item = Result<T>>
+    where
+        T: Unpin + DeserializeOwned,
+    {
+        Box::pin(run::new(self.clone(), args))
+    }
 }
 
 // for debug purposes only
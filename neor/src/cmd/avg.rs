@@ -49,3 +49,13 @@ impl AvgArg for Command {
         Some(self)
     }
 }
+
+impl<F, R> AvgArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_avg_opts(self) -> Option<Command> {
+        Some(Func::from_fn1(self).0)
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::arguments::{BatcherOption, GetAllOption};
+use crate::err::{ReqlDriverError, ReqlError};
+use crate::{args, r, Command, Func, Result, Session};
+
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+const DEFAULT_DELAY: Duration = Duration::from_millis(1);
+
+type Documents = Arc<Vec<Value>>;
+type Waiter = oneshot::Sender<Result<Documents>>;
+
+pub(crate) fn new(table: &Command, session: &Session, opts: BatcherOption) -> Batcher {
+    Batcher {
+        inner: Arc::new(Inner {
+            session: session.clone(),
+            table: table.clone(),
+            get_all: opts.get_all,
+            max_batch_size: opts.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE).max(1),
+            delay: opts.delay.unwrap_or(DEFAULT_DELAY),
+            cache: opts.cache.unwrap_or(true),
+            state: Mutex::new(State::default()),
+        }),
+    }
+}
+
+/// Loads documents by key, grouping the keys loaded at the
+/// same time into one query, returned by [batcher](crate::Command::batcher)
+///
+/// The first key loaded waits for the others during the `delay` of
+/// the [BatcherOption], or until `max_batch_size` keys are waiting.
+/// Their documents are then read by a single query running `get_all`
+/// for each key, and every caller gets the documents of its own key.
+///
+/// A key loaded again while its query runs waits for the same query.
+/// With the cache enabled, the documents of a key are only loaded
+/// once, so a batcher is meant to live as long as a request.
+#[derive(Debug, Clone)]
+pub struct Batcher {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    session: Session,
+    table: Command,
+    get_all: GetAllOption,
+    max_batch_size: usize,
+    delay: Duration,
+    cache: bool,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The keys waiting for the next query
+    pending: Vec<Value>,
+    /// The callers of each key pending or being loaded, by its JSON
+    waiters: HashMap<String, Vec<Waiter>>,
+    cache: HashMap<String, Documents>,
+    scheduled: bool,
+}
+
+impl Batcher {
+    /// Load the document of `key`, the first one if the
+    /// index of the batcher returns several documents.
+    pub async fn load<T>(&self, key: impl Serialize) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let documents = self.documents(key).await?;
+
+        match documents.first() {
+            Some(document) => Ok(Some(T::deserialize(document)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Load every document of `key`.
+    pub async fn load_many<T>(&self, key: impl Serialize) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let documents = self.documents(key).await?;
+
+        documents
+            .iter()
+            .map(|document| Ok(T::deserialize(document)?))
+            .collect()
+    }
+
+    /// Forget the cached documents of `key`, after it was written.
+    pub fn clear(&self, key: impl Serialize) -> Result<()> {
+        let key = serde_json::to_string(&key)?;
+        self.inner.state().cache.remove(&key);
+        Ok(())
+    }
+
+    /// Forget every cached document.
+    pub fn clear_all(&self) {
+        self.inner.state().cache.clear();
+    }
+
+    async fn documents(&self, key: impl Serialize) -> Result<Documents> {
+        let key = serde_json::to_value(key)?;
+        let id = key.to_string();
+        let (sender, receiver) = oneshot::channel();
+
+        {
+            let mut state = self.inner.state();
+            if let Some(documents) = state.cache.get(&id) {
+                return Ok(documents.clone());
+            }
+
+            match state.waiters.get_mut(&id) {
+                Some(waiters) => waiters.push(sender),
+                None => {
+                    state.waiters.insert(id, vec![sender]);
+                    state.pending.push(key);
+                }
+            }
+
+            if state.pending.len() >= self.inner.max_batch_size {
+                let keys = mem::take(&mut state.pending);
+                tokio::spawn(self.inner.clone().load(keys));
+            } else if !state.scheduled {
+                state.scheduled = true;
+                let inner = self.inner.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(inner.delay).await;
+                    let keys = {
+                        let mut state = inner.state();
+                        state.scheduled = false;
+                        mem::take(&mut state.pending)
+                    };
+                    inner.load(keys).await;
+                });
+            }
+        }
+
+        receiver
+            .await
+            .map_err(|_| ReqlDriverError::Other("the batch was dropped".to_owned()))?
+    }
+}
+
+impl Inner {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    async fn load(self: Arc<Self>, keys: Vec<Value>) {
+        if keys.is_empty() {
+            return;
+        }
+
+        let ids: Vec<_> = keys.iter().map(Value::to_string).collect();
+        let result = self.query(keys).await;
+        let mut state = self.state();
+
+        match result {
+            Ok(batch) => {
+                for (id, documents) in ids.into_iter().zip(batch) {
+                    let documents = Arc::new(documents);
+                    let waiters = state.waiters.remove(&id).unwrap_or_default();
+                    if self.cache {
+                        state.cache.insert(id, documents.clone());
+                    }
+                    for waiter in waiters {
+                        let _ = waiter.send(Ok(documents.clone()));
+                    }
+                }
+            }
+            Err(error) => {
+                for id in ids {
+                    for waiter in state.waiters.remove(&id).unwrap_or_default() {
+                        let _ = waiter.send(Err(error.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read the documents of every key, in the order of the keys.
+    async fn query(&self, keys: Vec<Value>) -> Result<Vec<Vec<Value>>> {
+        let len = keys.len();
+        let table = self.table.clone();
+        let get_all = self.get_all.clone();
+        let query = r.expr(keys).map(Func::from_fn1(move |key| {
+            table
+                .get_all(args!(key, get_all.clone()))
+                .coerce_to("array")
+        }));
+
+        let documents = query
+            .run_as::<Vec<Vec<Value>>>(&self.session)
+            .await?
+            .unwrap_or_default();
+
+        if documents.len() != len {
+            return Err(ReqlError::from(ReqlDriverError::Other(format!(
+                "expected the documents of {} keys, got {}",
+                len,
+                documents.len()
+            ))));
+        }
+        Ok(documents)
+    }
+}
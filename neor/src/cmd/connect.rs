@@ -22,6 +22,8 @@ use crate::constants::{
     DEFAULT_RETHINKDB_PORT, DEFAULT_RETHINKDB_USER, RETHINKDB_DRIVER_NAME,
 };
 use crate::err::ReqlDriverError;
+use crate::intercept::{Interceptor, Interceptors};
+use crate::redact::Redaction;
 use crate::{InnerSession, Result, Session, StaticString, TcpStreamConnection};
 
 #[derive(Debug)]
@@ -45,6 +47,15 @@ pub struct ConnectionCommand {
     timeout: Option<Duration>,
 
     tls_connector: Option<TlsConnector>,
+
+    /// Whether errors of the server keep the query which failed, by default `false`.
+    query_in_errors: bool,
+
+    /// How queries and responses are written in the logs, by default
+    /// masking the [DEFAULT_MASKED_FIELDS](crate::redact::DEFAULT_MASKED_FIELDS).
+    log_redaction: Redaction,
+
+    interceptors: Interceptors,
 }
 
 #[derive(Debug, Clone)]
@@ -113,6 +124,29 @@ impl ConnectionCommand {
         self
     }
 
+    /// Keep the query which failed in the errors of the server,
+    /// see [ServerError](crate::err::ServerError), rendered with the
+    /// [log_redaction](Self::log_redaction) of the session. Queries may
+    /// be large, so they are not kept by default.
+    pub fn query_in_errors(mut self, query_in_errors: bool) -> Self {
+        self.query_in_errors = query_in_errors;
+        self
+    }
+
+    /// Hide sensitive values from the queries and responses
+    /// traced by the driver, see [Redaction].
+    pub fn log_redaction(mut self, log_redaction: Redaction) -> Self {
+        self.log_redaction = log_redaction;
+        self
+    }
+
+    /// Add an interceptor called around every query of the session,
+    /// see [Interceptor].
+    pub fn interceptor(self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
     /// This method set ssl connection
     pub fn ssl_context(mut self, ssl_context: SslContext) -> Self {
         let mut file = File::open(ssl_context.ca_certs).unwrap();
@@ -180,6 +214,7 @@ impl ConnectionCommand {
                 None
             },
             stream,
+            write_buf: Vec::new(),
         };
 
         if let Some(tcp_stream) = stream.tls_stream {
@@ -195,10 +230,15 @@ impl ConnectionCommand {
             token: AtomicU64::new(0),
             broken: AtomicBool::new(false),
             change_feed: AtomicBool::new(false),
+            query_in_errors: self.query_in_errors,
+            redaction: self.log_redaction,
+            interceptors: self.interceptors,
+            stats: Default::default(),
         };
 
         Ok(Session {
             inner: Arc::new(inner),
+            defaults: Default::default(),
         })
     }
 }
@@ -213,6 +253,9 @@ impl Default for ConnectionCommand {
             password: DEFAULT_RETHINKDB_PASSWORD.static_string(),
             timeout: None,
             tls_connector: None,
+            query_in_errors: false,
+            log_redaction: Redaction::default(),
+            interceptors: Interceptors::default(),
         }
     }
 }
@@ -317,7 +360,7 @@ mod tools {
         fn validate(resp: &[u8]) -> Result<()> {
             let info = serde_json::from_slice::<ServerInfo>(resp)?;
             if !info.success {
-                return Err(err::ReqlRuntimeError::Internal(bytes_to_string(resp)).into());
+                return Err(err::ReqlRuntimeError::Internal(bytes_to_string(resp).into()).into());
             }
             #[allow(clippy::absurd_extreme_comparisons)]
             if PROTOCOL_VERSION < info.min_protocol_version
@@ -391,7 +434,7 @@ mod tools {
                         return Err(err::ReqlDriverError::Auth(msg).into());
                     }
                 }
-                return Err(err::ReqlRuntimeError::Internal(bytes_to_string(resp)).into());
+                return Err(err::ReqlRuntimeError::Internal(bytes_to_string(resp).into()).into());
             }
             Ok(info)
         }
@@ -441,8 +484,12 @@ mod test {
             Err(err) => {
                 if let ReqlError::Driver(err) = err {
                     match err {
-                        ReqlDriverError::Io(err, msg) => {
-                            assert!(std::io::ErrorKind::ConnectionRefused.eq(&err), "{}", msg)
+                        ReqlDriverError::Io(err) => {
+                            assert!(
+                                std::io::ErrorKind::ConnectionRefused.eq(&err.kind()),
+                                "{}",
+                                err
+                            )
                         }
                         ReqlDriverError::Auth(msg) => assert!(true, "{}", msg),
                         _ => (),
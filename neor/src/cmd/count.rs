@@ -44,3 +44,13 @@ where
         Some(Command::from_json(self.0))
     }
 }
+
+impl<F, R> CountArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_count_arg(self) -> Option<Command> {
+        Some(Func::from_fn1(self).0)
+    }
+}
@@ -1,15 +1,17 @@
 use ql2::term::TermType;
 
 use crate::arguments::{Args, FilterOption};
-use crate::{Command, CommandArg};
+use crate::{Command, CommandArg, Func};
 
-pub(crate) fn new(args: impl FilterArg) -> Command {
+pub(crate) fn new<A>(args: impl FilterArg<A>) -> Command {
     let (arg, opts) = args.into_filter_opts();
 
     arg.add_to_cmd(TermType::Filter).with_opts(opts)
 }
 
-pub trait FilterArg {
+/// `A` is `()` for values and `(Command,)` for closures,
+/// so that both can be passed to `filter`.
+pub trait FilterArg<A = ()> {
     fn into_filter_opts(self) -> (CommandArg, FilterOption);
 }
 
@@ -30,3 +32,23 @@ where
         (self.0 .0.into(), self.0 .1)
     }
 }
+
+impl<F, R> FilterArg<(Command,)> for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_filter_opts(self) -> (CommandArg, FilterOption) {
+        (Func::from_fn1(self).into(), Default::default())
+    }
+}
+
+impl<F, R> FilterArg<(Command,)> for Args<(F, FilterOption)>
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_filter_opts(self) -> (CommandArg, FilterOption) {
+        (Func::from_fn1(self.0 .0).into(), self.0 .1)
+    }
+}
@@ -1,7 +1,53 @@
 use ql2::term::TermType;
 
-use crate::{Command, CommandArg, Func};
+use crate::arguments::{Args, FoldOption};
+use crate::{Command, CommandArg, Func, IntoFunc};
 
-pub(crate) fn new(base: impl Into<CommandArg>, func: Func) -> Command {
-    base.into().add_to_cmd(TermType::Fold).with_arg(func.0)
+pub(crate) fn new(base: impl Into<CommandArg>, args: impl FoldArg) -> Command {
+    let (func, opts) = args.into_fold_opts();
+    let mut command = base.into().add_to_cmd(TermType::Fold).with_arg(func);
+
+    if let Some(emit) = opts.emit {
+        command = command.with_opt("emit", emit);
+    }
+    if let Some(final_emit) = opts.final_emit {
+        command = command.with_opt("final_emit", final_emit);
+    }
+    command
+}
+
+pub trait FoldArg {
+    fn into_fold_opts(self) -> (Func, FoldOption);
+}
+
+impl FoldArg for Func {
+    fn into_fold_opts(self) -> (Func, FoldOption) {
+        (self, Default::default())
+    }
+}
+
+impl FoldArg for Args<(Func, FoldOption)> {
+    fn into_fold_opts(self) -> (Func, FoldOption) {
+        self.0
+    }
+}
+
+impl<F, R> FoldArg for F
+where
+    F: FnOnce(Command, Command) -> R,
+    R: Into<Command>,
+{
+    fn into_fold_opts(self) -> (Func, FoldOption) {
+        (self.into_func(), Default::default())
+    }
+}
+
+impl<F, R> FoldArg for Args<(F, FoldOption)>
+where
+    F: FnOnce(Command, Command) -> R,
+    R: Into<Command>,
+{
+    fn into_fold_opts(self) -> (Func, FoldOption) {
+        (self.0 .0.into_func(), self.0 .1)
+    }
 }
@@ -1,7 +1,7 @@
 use ql2::term::TermType;
 
-use crate::{Command, Func};
+use crate::{Command, IntoFunc};
 
-pub(crate) fn new(arg: Func) -> Command {
-    Command::new(TermType::ForEach).with_arg(arg.0)
+pub(crate) fn new(arg: impl IntoFunc<(Command,)>) -> Command {
+    Command::new(TermType::ForEach).with_arg(arg.into_func())
 }
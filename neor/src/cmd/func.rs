@@ -1,4 +1,7 @@
-use crate::Command;
+use std::cmp::Ordering;
+
+use crate::proto::Query;
+use crate::{var_counter, Command};
 use ql2::term::TermType;
 
 #[derive(Debug, Clone)]
@@ -15,6 +18,90 @@ impl Func {
                 .with_arg(body),
         )
     }
+
+    fn to_term(&self) -> Option<String> {
+        serde_json::to_string(&Query(&self.0)).ok()
+    }
+}
+
+/// Functions are equal when they serialize to the same term.
+/// Each closure binds fresh variables, so two functions built
+/// from the same closure are not equal, but their clones are.
+impl PartialEq for Func {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.to_term(), other.to_term()) {
+            (Some(term), Some(other)) => term == other,
+            _ => false,
+        }
+    }
+}
+
+/// Only equal functions are ordered.
+impl PartialOrd for Func {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self == other).then_some(Ordering::Equal)
+    }
+}
+
+/// A value that can be turned into a ReQL function.
+///
+/// It is implemented by [Func] and by closures taking
+/// up to six [Command](crate::Command) arguments.
+/// `Args` is the tuple of argument types of the closure.
+pub trait IntoFunc<Args> {
+    fn into_func(self) -> Func;
+}
+
+impl<Args> IntoFunc<Args> for Func {
+    fn into_func(self) -> Func {
+        self
+    }
+}
+
+macro_rules! from_fn {
+    (@command $arg:ident) => { Command };
+    ($($name:ident => ($($arg:ident),*)),* $(,)?) => {
+        impl Func {
+            $(
+                /// Build a function from a closure, without the `func!` macro.
+                ///
+                /// Each argument of the closure is bound to
+                /// a fresh ReQL variable.
+                pub fn $name<F, R>(func: F) -> Self
+                where
+                    F: FnOnce($(from_fn!(@command $arg)),*) -> R,
+                    R: Into<Command>,
+                {
+                    func.into_func()
+                }
+            )*
+        }
+
+        $(
+            impl<F, R> IntoFunc<($(from_fn!(@command $arg),)*)> for F
+            where
+                F: FnOnce($(from_fn!(@command $arg)),*) -> R,
+                R: Into<Command>,
+            {
+                fn into_func(self) -> Func {
+                    $(let $arg = var_counter();)*
+                    let body = self($(Command::var($arg)),*);
+
+                    Func::new(vec![$($arg),*], body)
+                }
+            }
+        )*
+    };
+}
+
+from_fn! {
+    from_fn0 => (),
+    from_fn1 => (a),
+    from_fn2 => (a, b),
+    from_fn3 => (a, b, c),
+    from_fn4 => (a, b, c, d),
+    from_fn5 => (a, b, c, d, e),
+    from_fn6 => (a, b, c, d, e, f),
 }
 
 impl From<Func> for Command {
@@ -22,3 +109,23 @@ impl From<Func> for Command {
         func.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::arguments::{InsertOption, Interleave};
+    use crate::Func;
+
+    #[test]
+    fn test_func_eq() {
+        let func = Func::from_fn1(|row| row.g("title"));
+        let other = Func::from_fn1(|row| row.g("title"));
+
+        assert!(func == func.clone());
+        assert!(func != other);
+        assert!(Interleave::Func(func.clone()) == Interleave::Func(func.clone()));
+        assert!(
+            InsertOption::default().conflict_func(func.clone())
+                != InsertOption::default().conflict_func(other)
+        );
+    }
+}
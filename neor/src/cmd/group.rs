@@ -37,6 +37,12 @@ impl GroupArg for Func {
     }
 }
 
+impl GroupArg for Command {
+    fn into_group_opts(self) -> (CmdOpts, GroupOption) {
+        (CmdOpts::Single(self), Default::default())
+    }
+}
+
 impl<const N: usize> GroupArg for [Func; N] {
     fn into_group_opts(self) -> (CmdOpts, GroupOption) {
         let args = self.into_iter().map(|func| func.0).collect();
@@ -69,6 +75,12 @@ impl GroupArg for Args<(Func, GroupOption)> {
     }
 }
 
+impl GroupArg for Args<(Command, GroupOption)> {
+    fn into_group_opts(self) -> (CmdOpts, GroupOption) {
+        (CmdOpts::Single(self.0 .0), self.0 .1)
+    }
+}
+
 impl<const N: usize> GroupArg for Args<([Func; N], GroupOption)> {
     fn into_group_opts(self) -> (CmdOpts, GroupOption) {
         let funcs = self.0 .0.into_iter().map(|func| func.0).collect();
@@ -76,3 +88,25 @@ impl<const N: usize> GroupArg for Args<([Func; N], GroupOption)> {
         (CmdOpts::Many(funcs), self.0 .1)
     }
 }
+
+impl<F, R> GroupArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_group_opts(self) -> (CmdOpts, GroupOption) {
+        (CmdOpts::Single(Func::from_fn1(self).0), Default::default())
+    }
+}
+
+impl<F, R> GroupArg for Args<(F, GroupOption)>
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_group_opts(self) -> (CmdOpts, GroupOption) {
+        let Func(func) = Func::from_fn1(self.0 .0);
+
+        (CmdOpts::Single(func), self.0 .1)
+    }
+}
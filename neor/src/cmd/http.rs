@@ -1,42 +1,43 @@
 use ql2::term::TermType;
-use serde::Serialize;
 
-use crate::arguments::Args;
+use crate::arguments::{Args, HttpOption, Page};
 use crate::Command;
 
-pub(crate) fn new<T>(args: impl HttpArg<T>) -> Command
-where
-    T: Serialize,
-{
-    let (arg, opts) = args.into_http_opts();
-    let mut command = Command::new(TermType::Http).with_arg(arg);
+pub(crate) fn new(args: impl HttpArg) -> Command {
+    let (arg, mut opts) = args.into_http_opts();
+    let page = match opts.page.take() {
+        Some(Page::Func(func)) => Some(func),
+        page => {
+            opts.page = page;
+            None
+        }
+    };
+    let command = Command::new(TermType::Http).with_arg(arg).with_opts(opts);
 
-    if let Some(opts) = opts {
-        command = command.with_opts(opts);
+    match page {
+        Some(func) => command.with_opt("page", func),
+        None => command,
     }
-
-    command
 }
 
-pub trait HttpArg<T: Serialize> {
-    fn into_http_opts(self) -> (Command, Option<T>);
+pub trait HttpArg {
+    fn into_http_opts(self) -> (Command, HttpOption);
 }
 
-impl<T> HttpArg<T> for T
+impl<T> HttpArg for T
 where
-    T: Into<String> + Serialize,
+    T: Into<String>,
 {
-    fn into_http_opts(self) -> (Command, Option<T>) {
-        (Command::from_json(self.into()), None)
+    fn into_http_opts(self) -> (Command, HttpOption) {
+        (Command::from_json(self.into()), Default::default())
     }
 }
 
-impl<S, T> HttpArg<T> for Args<(S, T)>
+impl<S> HttpArg for Args<(S, HttpOption)>
 where
     S: Into<String>,
-    T: Serialize,
 {
-    fn into_http_opts(self) -> (Command, Option<T>) {
-        (Command::from_json(self.0 .0.into()), Some(self.0 .1))
+    fn into_http_opts(self) -> (Command, HttpOption) {
+        (Command::from_json(self.0 .0.into()), self.0 .1)
     }
 }
@@ -1,28 +1,31 @@
 use ql2::term::TermType;
 
 use crate::arguments::{Args, IndexCreateOption};
+use crate::types::Binary;
 use crate::{Command, CommandArg, Func};
 
-pub(crate) fn new(args: impl IndexCreateArg) -> Command {
+pub(crate) fn new<A>(args: impl IndexCreateArg<A>) -> Command {
     let (arg, func, opts) = args.into_table_create_opts();
     let mut command = arg.add_to_cmd(TermType::IndexCreate);
 
-    if let Some(Func(func)) = func {
+    if let Some(func) = func {
         command = command.with_arg(func);
     }
 
     command.with_opts(opts)
 }
 
-pub trait IndexCreateArg {
-    fn into_table_create_opts(self) -> (CommandArg, Option<Func>, IndexCreateOption);
+/// `A` is `()` for values and `(Command,)` for closures,
+/// so that both can be passed to `index_create`.
+pub trait IndexCreateArg<A = ()> {
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption);
 }
 
 impl<T> IndexCreateArg for T
 where
     T: Into<CommandArg>,
 {
-    fn into_table_create_opts(self) -> (CommandArg, Option<Func>, IndexCreateOption) {
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
         (self.into(), None, Default::default())
     }
 }
@@ -31,8 +34,8 @@ impl<T> IndexCreateArg for Args<(T, Func)>
 where
     T: Into<CommandArg>,
 {
-    fn into_table_create_opts(self) -> (CommandArg, Option<Func>, IndexCreateOption) {
-        (self.0 .0.into(), Some(self.0 .1), Default::default())
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        (self.0 .0.into(), Some(self.0 .1 .0), Default::default())
     }
 }
 
@@ -40,7 +43,7 @@ impl<T> IndexCreateArg for Args<(T, IndexCreateOption)>
 where
     T: Into<CommandArg>,
 {
-    fn into_table_create_opts(self) -> (CommandArg, Option<Func>, IndexCreateOption) {
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
         (self.0 .0.into(), None, self.0 .1)
     }
 }
@@ -49,7 +52,59 @@ impl<T> IndexCreateArg for Args<(T, Func, IndexCreateOption)>
 where
     T: Into<CommandArg>,
 {
-    fn into_table_create_opts(self) -> (CommandArg, Option<Func>, IndexCreateOption) {
-        (self.0 .0.into(), Some(self.0 .1), self.0 .2)
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        (self.0 .0.into(), Some(self.0 .1 .0), self.0 .2)
+    }
+}
+
+impl<T, F, R> IndexCreateArg<(Command,)> for Args<(T, F)>
+where
+    T: Into<CommandArg>,
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        let Func(func) = Func::from_fn1(self.0 .1);
+
+        (self.0 .0.into(), Some(func), Default::default())
+    }
+}
+
+impl<T, F, R> IndexCreateArg<(Command,)> for Args<(T, F, IndexCreateOption)>
+where
+    T: Into<CommandArg>,
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        let Func(func) = Func::from_fn1(self.0 .1);
+
+        (self.0 .0.into(), Some(func), self.0 .2)
+    }
+}
+
+impl<T> IndexCreateArg for Args<(T, Binary)>
+where
+    T: Into<CommandArg>,
+{
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        (
+            self.0 .0.into(),
+            Some(Command::from_json(self.0 .1)),
+            Default::default(),
+        )
+    }
+}
+
+impl<T> IndexCreateArg for Args<(T, Binary, IndexCreateOption)>
+where
+    T: Into<CommandArg>,
+{
+    fn into_table_create_opts(self) -> (CommandArg, Option<Command>, IndexCreateOption) {
+        (
+            self.0 .0.into(),
+            Some(Command::from_json(self.0 .1)),
+            self.0 .2,
+        )
     }
 }
@@ -1,9 +1,9 @@
 use ql2::term::TermType;
 
-use crate::{Command, Func};
+use crate::{Command, IntoFunc};
 
-pub(crate) fn new(other_table: Command, func: Func) -> Command {
+pub(crate) fn new(other_table: Command, func: impl IntoFunc<(Command, Command)>) -> Command {
     Command::new(TermType::InnerJoin)
         .with_arg(other_table)
-        .with_arg(func.0)
+        .with_arg(func.into_func())
 }
@@ -4,9 +4,14 @@ use crate::arguments::{Args, InsertOption};
 use crate::{Command, CommandArg};
 
 pub(crate) fn new(args: impl InsertArg) -> Command {
-    let (arg, opts) = args.into_insert_opts();
+    let (arg, mut opts) = args.into_insert_opts();
+    let conflict_func = opts.conflict_func.take();
+    let command = arg.add_to_cmd(TermType::Insert).with_opts(opts);
 
-    arg.add_to_cmd(TermType::Insert).with_opts(opts)
+    match conflict_func {
+        Some(func) => command.with_opt("conflict", func),
+        None => command,
+    }
 }
 
 pub trait InsertArg {
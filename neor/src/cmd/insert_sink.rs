@@ -0,0 +1,350 @@
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::stream::FuturesUnordered;
+use futures::{ready, Sink, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use crate::arguments::{InsertOption, InsertSinkOption};
+use crate::err::{ReqlAvailabilityError, ReqlDriverError, ReqlError, ReqlRuntimeError};
+use crate::types::MutationResponse;
+use crate::{args, Command, Converter, Result, Session};
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+const DEFAULT_BATCH_BYTES: usize = 4 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 4;
+const DEFAULT_RETRIES: usize = 3;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub(crate) fn new<T>(table: &Command, session: &Session, opts: InsertSinkOption) -> InsertSink<T> {
+    InsertSink {
+        session: session.clone(),
+        table: table.clone(),
+        batch_size: opts.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+        batch_bytes: opts.batch_bytes.unwrap_or(DEFAULT_BATCH_BYTES),
+        concurrency: opts.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1),
+        retries: opts.retries.unwrap_or(DEFAULT_RETRIES),
+        retry_delay: opts.retry_delay.unwrap_or(DEFAULT_RETRY_DELAY),
+        insert: opts.insert,
+        buffer: Vec::new(),
+        buffer_bytes: 0,
+        in_flight: FuturesUnordered::new(),
+        batches: 0,
+        response: InsertSinkResponse::default(),
+        item: PhantomData,
+    }
+}
+
+/// Errors of a batch
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct BatchError {
+    /// the position of the batch, starting from 0.
+    pub batch: usize,
+    /// the number of documents which failed.
+    pub errors: usize,
+    pub first_error: String,
+}
+
+/// Aggregated result of the `insert` queries run by an [InsertSink]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct InsertSinkResponse {
+    /// the sum of the responses of every batch. Its `first_error`
+    /// is the one of the failed batch with the lowest position.
+    pub mutation: MutationResponse,
+    /// the `first_error` of every batch which failed,
+    /// ordered by position.
+    pub batch_errors: Vec<BatchError>,
+    /// the number of `insert` queries run.
+    pub batches: usize,
+}
+
+/// A [Sink] inserting documents in batches,
+/// returned by [insert_sink](crate::Command::insert_sink)
+///
+/// Documents are buffered until the batch reaches its size in
+/// documents or in bytes, then inserted by a query spawned on
+/// the tokio runtime, so that several batches are inserted at once.
+/// [flush](futures::SinkExt::flush) and [close](futures::SinkExt::close)
+/// wait for every batch to be inserted.
+///
+/// Documents rejected by the server are counted in the
+/// [response](Self::response), while query errors fail the sink
+/// and abort the batches still being inserted.
+pub struct InsertSink<T> {
+    session: Session,
+    table: Command,
+    insert: InsertOption,
+    batch_size: usize,
+    batch_bytes: usize,
+    concurrency: usize,
+    retries: usize,
+    retry_delay: Duration,
+    buffer: Vec<Value>,
+    buffer_bytes: usize,
+    in_flight: FuturesUnordered<JoinHandle<(usize, Result<MutationResponse>)>>,
+    batches: usize,
+    response: InsertSinkResponse,
+    item: PhantomData<fn(T)>,
+}
+
+impl<T> InsertSink<T> {
+    /// The response aggregated from the batches inserted so far.
+    pub fn response(&self) -> &InsertSinkResponse {
+        &self.response
+    }
+
+    pub fn into_response(self) -> InsertSinkResponse {
+        self.response
+    }
+
+    fn send_batch(&mut self) {
+        let documents = std::mem::take(&mut self.buffer);
+        let query = self.table.insert(args!(documents, self.insert.clone()));
+        let session = self.session.clone();
+        let batch = self.batches;
+        let retries = self.retries;
+        let retry_delay = self.retry_delay;
+
+        self.buffer_bytes = 0;
+        self.batches += 1;
+        self.in_flight.push(tokio::spawn(async move {
+            (batch, insert(query, session, retries, retry_delay).await)
+        }));
+    }
+
+    /// Poll the batches in flight, returning `Ready` once one
+    /// completed or when there is none.
+    fn poll_batch(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let (batch, result) = match ready!(self.in_flight.poll_next_unpin(cx)) {
+            Some(Ok(completed)) => completed,
+            Some(Err(error)) => {
+                return Poll::Ready(Err(
+                    self.fail(ReqlDriverError::Other(error.to_string()).into())
+                ))
+            }
+            None => return Poll::Ready(Ok(())),
+        };
+
+        match result {
+            Ok(response) => {
+                self.response.add(batch, response);
+                Poll::Ready(Ok(()))
+            }
+            Err(error) => Poll::Ready(Err(self.fail(error))),
+        }
+    }
+
+    /// Poll the batches in flight, returning `Ready` once
+    /// another batch can be sent.
+    fn poll_capacity(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // collect the batches already inserted, to report errors early
+        while !self.in_flight.is_empty() {
+            match self.poll_batch(cx) {
+                Poll::Ready(result) => result?,
+                Poll::Pending if self.in_flight.len() < self.concurrency => break,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Abort the batches in flight, since the sink failed.
+    fn fail(&mut self, error: ReqlError) -> ReqlError {
+        for batch in self.in_flight.iter() {
+            batch.abort();
+        }
+        self.in_flight.clear();
+        error
+    }
+
+    fn poll_empty(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        while !self.in_flight.is_empty() {
+            ready!(self.poll_batch(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: Serialize> Sink<T> for InsertSink<T> {
+    type Error = ReqlError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+
+        if this.buffer.len() >= this.batch_size {
+            ready!(this.poll_capacity(cx))?;
+            this.send_batch();
+        }
+
+        // `start_send` may send a batch when the document is too large
+        this.poll_capacity(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<()> {
+        let this = self.get_mut();
+        let document = serde_json::to_value(item)?;
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, &document)?;
+
+        // a full batch is sent by the next `poll_ready` or `poll_flush`,
+        // so that at most `concurrency` batches are in flight
+        if !this.buffer.is_empty() && this.buffer_bytes + counter.0 > this.batch_bytes {
+            this.send_batch();
+        }
+        this.buffer.push(document);
+        this.buffer_bytes += counter.0;
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+
+        if !this.buffer.is_empty() {
+            ready!(this.poll_capacity(cx))?;
+            this.send_batch();
+        }
+
+        this.poll_empty(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        <Self as Sink<T>>::poll_flush(self, cx)
+    }
+}
+
+async fn insert(
+    query: Command,
+    session: Session,
+    retries: usize,
+    mut retry_delay: Duration,
+) -> Result<MutationResponse> {
+    let mut attempts = 0;
+
+    loop {
+        let result = match query.run(&session).await {
+            Ok(response) => response.unwrap_or_default().parse(),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Err(ReqlError::Runtime(ReqlRuntimeError::Availability(
+                ReqlAvailabilityError::OpFailed(_),
+            ))) if attempts < retries => {
+                attempts += 1;
+                tokio::time::sleep(retry_delay).await;
+                retry_delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+impl InsertSinkResponse {
+    /// Add the response of the batch at position `batch`.
+    fn add(&mut self, batch: usize, response: MutationResponse) {
+        if let Some(first_error) = &response.first_error {
+            let position = self
+                .batch_errors
+                .partition_point(|error| error.batch < batch);
+            self.batch_errors.insert(
+                position,
+                BatchError {
+                    batch,
+                    errors: response.errors,
+                    first_error: first_error.to_owned(),
+                },
+            );
+        }
+        merge(&mut self.mutation, response);
+        self.mutation.first_error = self
+            .batch_errors
+            .first()
+            .map(|error| error.first_error.clone());
+        self.batches += 1;
+    }
+}
+
+fn merge(total: &mut MutationResponse, response: MutationResponse) {
+    total.inserted += response.inserted;
+    total.replaced += response.replaced;
+    total.unchanged += response.unchanged;
+    total.skipped += response.skipped;
+    total.deleted += response.deleted;
+    total.errors += response.errors;
+
+    if let Some(keys) = response.generated_keys {
+        total
+            .generated_keys
+            .get_or_insert_with(Vec::new)
+            .extend(keys);
+    }
+    if let Some(warnings) = response.warnings {
+        total.warnings.get_or_insert_with(Vec::new).extend(warnings);
+    }
+    if let Some(changes) = response.changes {
+        total.changes.get_or_insert_with(Vec::new).extend(changes);
+    }
+}
+
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::MutationResponse;
+
+    use super::InsertSinkResponse;
+
+    #[test]
+    fn test_add_responses() {
+        let mut total = InsertSinkResponse::default();
+        let first = MutationResponse {
+            inserted: 1,
+            errors: 1,
+            first_error: Some("Duplicate primary key `id`: 1".to_owned()),
+            ..Default::default()
+        };
+        let second = MutationResponse {
+            inserted: 2,
+            ..Default::default()
+        };
+        let third = MutationResponse {
+            errors: 1,
+            first_error: Some("Duplicate primary key `id`: 7".to_owned()),
+            ..Default::default()
+        };
+
+        // batches complete in any order
+        total.add(2, third);
+        total.add(1, second);
+        total.add(0, first);
+
+        assert_eq!(total.batches, 3);
+        assert_eq!(total.mutation.inserted, 3);
+        assert_eq!(total.mutation.errors, 2);
+        assert_eq!(
+            total.mutation.first_error.as_deref(),
+            Some("Duplicate primary key `id`: 1")
+        );
+        let batches: Vec<_> = total.batch_errors.iter().map(|error| error.batch).collect();
+        assert_eq!(batches, [0, 2]);
+    }
+}
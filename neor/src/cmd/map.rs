@@ -2,7 +2,7 @@ use ql2::term::TermType;
 
 use crate::arguments::Args;
 use crate::command_tools::CmdOpts;
-use crate::{Command, Func};
+use crate::{Command, Func, IntoFunc};
 
 pub(crate) fn new(args: impl MapArg) -> Command {
     let (args, func) = args.into_map_opts();
@@ -25,6 +25,12 @@ impl MapArg for Func {
     }
 }
 
+impl MapArg for Command {
+    fn into_map_opts(self) -> (Option<CmdOpts>, Command) {
+        (None, self)
+    }
+}
+
 impl MapArg for Args<(Command, Func)> {
     fn into_map_opts(self) -> (Option<CmdOpts>, Command) {
         let Func(func) = self.0 .1;
@@ -43,3 +49,52 @@ where
         (Some(CmdOpts::Many(self.0 .0.into_iter().collect())), func)
     }
 }
+
+impl<F, R> MapArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_map_opts(self) -> (Option<CmdOpts>, Command) {
+        (None, Func::from_fn1(self).0)
+    }
+}
+
+impl<F, R> MapArg for Args<(Command, F)>
+where
+    F: FnOnce(Command, Command) -> R,
+    R: Into<Command>,
+{
+    fn into_map_opts(self) -> (Option<CmdOpts>, Command) {
+        let Func(func) = Func::from_fn2(self.0 .1);
+
+        (Some(CmdOpts::Single(self.0 .0)), func)
+    }
+}
+
+macro_rules! map_sequences {
+    (@command $arg:ident) => { Command };
+    ($($len:literal => ($($arg:ident),*)),* $(,)?) => {
+        $(
+            impl<F, R> MapArg for Args<([Command; $len], F)>
+            where
+                F: FnOnce($(map_sequences!(@command $arg)),*) -> R,
+                R: Into<Command>,
+            {
+                fn into_map_opts(self) -> (Option<CmdOpts>, Command) {
+                    let Func(func) = self.0 .1.into_func();
+
+                    (Some(CmdOpts::Many(self.0 .0.into_iter().collect())), func)
+                }
+            }
+        )*
+    };
+}
+
+map_sequences! {
+    1 => (a, b),
+    2 => (a, b, c),
+    3 => (a, b, c, d),
+    4 => (a, b, c, d, e),
+    5 => (a, b, c, d, e, f),
+}
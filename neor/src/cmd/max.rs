@@ -57,3 +57,13 @@ impl MaxArg for Command {
         (Some(self), Default::default())
     }
 }
+
+impl<F, R> MaxArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_max_opts(self) -> (Option<Command>, MaxOption) {
+        (Some(Func::from_fn1(self).0), Default::default())
+    }
+}
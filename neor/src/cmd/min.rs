@@ -57,3 +57,13 @@ impl MinArg for Command {
         (Some(self), Default::default())
     }
 }
+
+impl<F, R> MinArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_min_opts(self) -> (Option<Command>, MinOption) {
+        (Some(Func::from_fn1(self).0), Default::default())
+    }
+}
@@ -8,7 +8,7 @@ use crate::{Command, CommandArg, Func};
 
 use super::index::Index;
 
-pub(crate) fn new(args: impl OrderByArg) -> Command {
+pub(crate) fn new<A>(args: impl OrderByArg<A>) -> Command {
     let (args, index) = args.into_order_by_opts();
     let mut command = Command::new(TermType::OrderBy);
 
@@ -17,13 +17,15 @@ pub(crate) fn new(args: impl OrderByArg) -> Command {
     }
 
     if let Some(index) = index {
-        command = command.with_arg(index.0)
+        command = command.with_opt("index", index.0)
     }
 
     command
 }
 
-pub trait OrderByArg {
+/// `A` is `()` for values and `(Command,)` for closures,
+/// so that both can be passed to `order_by`.
+pub trait OrderByArg<A = ()> {
     fn into_order_by_opts(self) -> (Option<CmdOpts>, Option<Index>);
 }
 
@@ -51,6 +53,19 @@ impl OrderByArg for Func {
     }
 }
 
+impl<F, R> OrderByArg<(Command,)> for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_order_by_opts(self) -> (Option<CmdOpts>, Option<Index>) {
+        (
+            Some(CmdOpts::Single(Func::from_fn1(self).0)),
+            Default::default(),
+        )
+    }
+}
+
 impl OrderByArg for Command {
     fn into_order_by_opts(self) -> (Option<CmdOpts>, Option<Index>) {
         (Some(CmdOpts::Single(self)), Default::default())
@@ -69,6 +84,19 @@ where
     }
 }
 
+impl<F, R> OrderByArg<(Command,)> for Args<(F, Index)>
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_order_by_opts(self) -> (Option<CmdOpts>, Option<Index>) {
+        (
+            Some(CmdOpts::Single(Func::from_fn1(self.0 .0).0)),
+            Some(self.0 .1),
+        )
+    }
+}
+
 impl<T, const N: usize> OrderByArg for Args<[T; N]>
 where
     T: Into<CommandArg>,
@@ -82,3 +110,27 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::proto::Query;
+    use crate::{args, r};
+
+    #[test]
+    fn test_order_by_index_option() {
+        let posts = r.table("posts");
+        let by_index = posts.order_by(r.index(r.desc("id")));
+        let by_field_and_index = posts.order_by(args!("title", r.index("id")));
+
+        assert_eq!(
+            serde_json::to_value(Query(&by_index)).unwrap(),
+            json!([41, [[15, ["posts"], {}]], {"index": [74, ["id"]]}])
+        );
+        assert_eq!(
+            serde_json::to_value(Query(&by_field_and_index)).unwrap(),
+            json!([41, [[15, ["posts"], {}], "title"], {"index": "id"}])
+        );
+    }
+}
@@ -1,9 +1,9 @@
 use ql2::term::TermType;
 
-use crate::{Command, Func};
+use crate::{Command, Func, IntoFunc};
 
-pub(crate) fn new(other_table: Command, func: Func) -> Command {
-    let Func(func) = func;
+pub(crate) fn new(other_table: Command, func: impl IntoFunc<(Command, Command)>) -> Command {
+    let Func(func) = func.into_func();
 
     Command::new(TermType::OuterJoin)
         .with_arg(other_table)
@@ -0,0 +1,327 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::arguments::{BetweenOption, Status};
+use crate::err::{ReqlDriverError, ReqlError};
+use crate::{args, r, Command, Result};
+
+use super::run::RunArg;
+
+pub(crate) fn new(
+    table: &Command,
+    index: impl Into<PageIndex>,
+    page_size: usize,
+    after: Option<Cursor>,
+) -> Paginate {
+    Paginate {
+        table: table.clone(),
+        index: index.into(),
+        page_size: page_size.max(1),
+        after,
+    }
+}
+
+/// The index a table is paginated with
+///
+/// The documents are ordered by the index, and the next page starts
+/// after the key of the last document of the previous one. The key
+/// is read from the fields of the document, so the index must be a
+/// field, or a compound index of several fields.
+///
+/// Since the documents sharing the last key of a page would be skipped,
+/// the keys must be unique: the index must be the primary key, or a
+/// compound index ending with it. The query fails with other indexes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageIndex {
+    name: Cow<'static, str>,
+    fields: Vec<Cow<'static, str>>,
+    compound: bool,
+    descending: bool,
+}
+
+impl PageIndex {
+    /// The primary key `name`.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        let name = name.into();
+
+        Self {
+            fields: vec![name.clone()],
+            name,
+            compound: false,
+            descending: false,
+        }
+    }
+
+    /// The compound index `name` on `fields`, in the order of the index.
+    pub fn compound<F>(name: impl Into<Cow<'static, str>>, fields: F) -> Self
+    where
+        F: IntoIterator,
+        F::Item: Into<Cow<'static, str>>,
+    {
+        Self {
+            name: name.into(),
+            fields: fields.into_iter().map(Into::into).collect(),
+            compound: true,
+            descending: false,
+        }
+    }
+
+    /// Read the pages in descending order.
+    pub fn desc(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// The field which must be the primary key,
+    /// for the keys of the index to be unique.
+    fn primary_key(&self) -> &str {
+        self.fields.last().unwrap_or(&self.name)
+    }
+
+    /// The key of `document` in this index.
+    fn key(&self, document: &Value) -> Result<Value> {
+        let mut key = Vec::with_capacity(self.fields.len());
+
+        for field in &self.fields {
+            match document.get(field.as_ref()) {
+                Some(value) => key.push(value.clone()),
+                None => {
+                    return Err(ReqlDriverError::Other(format!(
+                        "the document has no field `{}` of the index `{}`",
+                        field, self.name
+                    ))
+                    .into())
+                }
+            }
+        }
+
+        if self.compound {
+            Ok(Value::Array(key))
+        } else {
+            Ok(key.pop().unwrap_or_default())
+        }
+    }
+}
+
+impl From<&'static str> for PageIndex {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for PageIndex {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+/// Where the next page starts, returned with a [Page]
+///
+/// A cursor is sent to clients as an opaque token with
+/// [to_string](ToString::to_string), and read back with
+/// [parse](str::parse). It is only valid with the index
+/// and the order of the page it was returned with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    index: Cow<'static, str>,
+    descending: bool,
+    key: Value,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| fmt::Error)?;
+        f.write_str(&base64::encode_config(json, base64::URL_SAFE_NO_PAD))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = ReqlError;
+
+    fn from_str(token: &str) -> Result<Self> {
+        base64::decode_config(token, base64::URL_SAFE_NO_PAD)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| ReqlDriverError::Other("invalid cursor".to_owned()).into())
+    }
+}
+
+/// A page of documents and the cursor of the next one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `None` on the last page
+    pub next: Option<Cursor>,
+}
+
+/// A page of a table, returned by [paginate](crate::Command::paginate)
+#[derive(Debug, Clone)]
+pub struct Paginate {
+    table: Command,
+    index: PageIndex,
+    page_size: usize,
+    after: Option<Cursor>,
+}
+
+impl Paginate {
+    /// The query of the page, reading one more document
+    /// to know whether a next page exists.
+    pub fn query(&self) -> Result<Command> {
+        let name = self.index.name.clone();
+        let mut query = self.table.clone();
+
+        if let Some(cursor) = &self.after {
+            if cursor.index != name || cursor.descending != self.index.descending {
+                return Err(ReqlDriverError::Other(format!(
+                    "the cursor does not belong to the index `{}` in this order",
+                    name
+                ))
+                .into());
+            }
+
+            let key = Command::from_json(&cursor.key);
+            let between_option = BetweenOption::default().index(&name);
+            query = if self.index.descending {
+                let between_option = between_option.right_bound(Status::Open);
+                query.between(args!(r::min_val(), key, between_option))
+            } else {
+                let between_option = between_option.left_bound(Status::Open);
+                query.between(args!(key, r::max_val(), between_option))
+            };
+        }
+
+        let index = if self.index.descending {
+            r.index(r.desc(name.into_owned()))
+        } else {
+            r.index(name.into_owned())
+        };
+
+        let page = query.order_by(index).limit(self.page_size + 1);
+        let not_unique = format!(
+            "the index `{}` does not end with the primary key, its keys may not be unique",
+            self.index.name
+        );
+
+        Ok(r.branch(
+            self.table
+                .info()
+                .g("primary_key")
+                .eq(self.index.primary_key()),
+            args!(page, r.error(not_unique)),
+        ))
+    }
+
+    /// Read the page.
+    pub async fn run<T>(&self, args: impl RunArg) -> Result<Page<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut documents = self
+            .query()?
+            .coerce_to("array")
+            .run_as::<Vec<Value>>(args)
+            .await?
+            .unwrap_or_default();
+
+        let next = if documents.len() > self.page_size {
+            documents.truncate(self.page_size);
+            let last = documents.last().map(|document| self.index.key(document));
+            last.transpose()?.map(|key| Cursor {
+                index: self.index.name.clone(),
+                descending: self.index.descending,
+                key,
+            })
+        } else {
+            None
+        };
+
+        let items = documents
+            .into_iter()
+            .map(|document| Ok(serde_json::from_value(document)?))
+            .collect::<Result<_>>()?;
+
+        Ok(Page { items, next })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Cursor, PageIndex};
+    use crate::proto::Query;
+    use crate::r;
+
+    #[test]
+    fn test_cursor_token() {
+        let cursor = Cursor {
+            index: "date_id".into(),
+            descending: true,
+            key: json!(["2022-01-01", 7]),
+        };
+        let token = cursor.to_string();
+
+        assert!(token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(token.parse::<Cursor>().unwrap(), cursor);
+        assert!("not a cursor".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn test_page_key() {
+        let post = json!({"id": 7, "date": "2022-01-01", "title": "Lorem"});
+
+        assert_eq!(PageIndex::new("id").key(&post).unwrap(), json!(7));
+        assert_eq!(
+            PageIndex::compound("date_id", ["date", "id"])
+                .key(&post)
+                .unwrap(),
+            json!(["2022-01-01", 7])
+        );
+        assert!(PageIndex::new("view").key(&post).is_err());
+    }
+
+    #[test]
+    fn test_next_page_query() {
+        let cursor = Cursor {
+            index: "id".into(),
+            descending: true,
+            key: json!(7),
+        };
+        let posts = r.table("posts");
+        let query = posts
+            .paginate(PageIndex::new("id").desc(), 10, Some(cursor.clone()))
+            .query()
+            .unwrap();
+
+        let query = serde_json::to_value(Query(&query)).unwrap();
+
+        assert_eq!(
+            query[1][0],
+            json!([
+                17,
+                [[31, [[79, [[15, ["posts"], {}]]], "primary_key"]], "id"]
+            ])
+        );
+        assert_eq!(
+            query[1][1],
+            json!([71, [
+                [41, [
+                    [182, [[15, ["posts"], {}], [180, []], 7], {
+                        "index": "id",
+                        "right_bound": "open"
+                    }]
+                ], {"index": [74, ["id"]]}],
+                11
+            ]])
+        );
+        assert_eq!(query[1][2][0], json!(12));
+        assert!(posts.paginate("id", 10, Some(cursor)).query().is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use ql2::term::TermType;
 
-use crate::{Command, Func};
+use crate::{Command, IntoFunc};
 
-pub(crate) fn new(func: Func) -> Command {
-    Command::new(TermType::Reduce).with_arg(func.0)
+pub(crate) fn new(func: impl IntoFunc<(Command, Command)>) -> Command {
+    Command::new(TermType::Reduce).with_arg(func.into_func())
 }
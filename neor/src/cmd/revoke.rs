@@ -0,0 +1,24 @@
+use ql2::term::TermType;
+use serde_json::{Map, Value};
+
+use crate::Command;
+
+// `grant` removes a permission from a scope when it is set to `null`,
+// which `Permission` cannot express as it skips the `None` fields.
+// `connect` only exists in the global scope.
+pub(crate) fn new(username: impl Into<String>, global: bool) -> Command {
+    let mut permissions = Map::new();
+    let fields: &[&str] = if global {
+        &["read", "write", "config", "connect"]
+    } else {
+        &["read", "write", "config"]
+    };
+
+    for field in fields {
+        permissions.insert(field.to_string(), Value::Null);
+    }
+
+    Command::new(TermType::Grant)
+        .with_arg(Command::from_json(username.into()))
+        .with_arg(Command::from(Value::Object(permissions)))
+}
@@ -1,5 +1,7 @@
 use std::borrow::Cow;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
 use std::{mem, str};
 
 use async_stream::try_stream;
@@ -10,11 +12,15 @@ use ql2::query::QueryType;
 use ql2::response::{ErrorType, ResponseType};
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use serde_json::Value;
-use tracing::trace;
+use serde_json::value::{RawValue, Value};
+use tracing::{debug, debug_span, field, trace, Instrument, Level, Span};
 
+use super::update_if::CONFLICT_ERROR;
 use crate::arguments::{Args, RunOption};
-use crate::constants::{DATA_SIZE, HEADER_SIZE, TOKEN_SIZE};
+use crate::constants::{
+    DATA_SIZE, DEFAULT_RETHINKDB_DBNAME, HEADER_SIZE, MAX_WRITE_BUF_CAPACITY, TOKEN_SIZE,
+};
+use crate::intercept::Interceptor;
 use crate::proto::{Payload, Query};
 use crate::{err, Command, Connection, Result, Session};
 
@@ -23,7 +29,8 @@ use crate::{err, Command, Connection, Result, Session};
 pub(crate) struct Response {
     t: i32,
     e: Option<i32>,
-    pub(crate) r: Value,
+    /// The result, kept as JSON to deserialize the rows straight from it
+    pub(crate) r: Box<RawValue>,
     b: Option<Value>,
     p: Option<Value>,
     n: Option<Value>,
@@ -34,7 +41,7 @@ impl Response {
         Self {
             t: ResponseType::SuccessAtom as i32,
             e: None,
-            r: Value::Array(Vec::new()),
+            r: RawValue::from_string("[]".to_owned()).unwrap(),
             b: None,
             p: None,
             n: None,
@@ -92,7 +99,7 @@ impl RunArg for Args<(&mut Session, RunOption)> {
     }
 }
 
-pub(crate) fn new<A, T>(query: Command, arg: A) -> impl Stream<Item = Result<T>>
+pub(crate) fn new<A, T>(mut query: Command, arg: A) -> impl Stream<Item = Result<T>>
 where
     A: RunArg,
     T: Unpin + DeserializeOwned,
@@ -100,26 +107,49 @@ where
     try_stream! {
         let (mut conn, mut opts) = arg.into_run_opts()?;
         opts = opts.default_db(&conn.session).await;
+        let interceptors = conn.session.inner.interceptors.snapshot();
+        for interceptor in &interceptors {
+            interceptor.before(&mut query, &mut opts)?;
+        }
         let change_feed = query.change_feed();
         if change_feed {
             conn.session.inner.mark_change_feed();
         }
         let noreply = opts.noreply.unwrap_or_default();
+        let span = debug_span!(
+            "query",
+            token = conn.token,
+            db = opts.db.as_ref().map_or(DEFAULT_RETHINKDB_DBNAME, |db| db.0.as_ref()),
+            table = query.root_table(),
+            term = ?query.term_type(),
+            response_type = field::Empty,
+            rows = field::Empty,
+            batches = field::Empty,
+            latency_ms = field::Empty,
+        );
+        let mut guard = QueryGuard::new(&conn.session, span.clone(), &query, interceptors);
         let mut payload = Payload(QueryType::Start, Some(Query(&query)), opts);
 
         loop {
-            let (response_type, resp) = conn.request(&payload, noreply).await?;
+            let (response_type, resp) = match conn.request(&payload, noreply).instrument(span.clone()).await {
+                Ok(response) => response,
+                Err(error) => Err(guard.failed(with_query(error, &query, &conn)))?,
+            };
+            guard.response(response_type, &resp.r);
             trace!("yielding response; token: {}", conn.token);
 
             match response_type {
                 ResponseType::SuccessAtom | ResponseType::ServerInfo => {
-                    for val in serde_json::from_value::<Vec<T>>(resp.r)? {
+                    let rows = parse_rows::<T>(&resp.r).map_err(|error| guard.failed(error))?;
+                    guard.rows += rows.len() as u64;
+                    for val in rows {
                         yield val;
                     }
                     break;
                 }
                 ResponseType::SuccessSequence => {
-                    yield serde_json::from_value::<T>(resp.r)?;
+                    guard.rows += count_rows(&resp.r);
+                    yield serde_json::from_str::<T>(resp.r.get()).map_err(|error| guard.failed(error.into()))?;
                     break;
                 }
                 ResponseType::SuccessPartial => {
@@ -130,19 +160,17 @@ where
                         break;
                     }
                     payload = Payload(QueryType::Continue, None, RunOption::default());
-                    // for val in serde_json::from_value::<Vec<T>>(resp.r)? {
-                    //     yield val;
-                    // }
-                    yield serde_json::from_value::<T>(resp.r)?;
+                    guard.rows += count_rows(&resp.r);
+                    yield serde_json::from_str::<T>(resp.r.get()).map_err(|error| guard.failed(error.into()))?;
                     continue;
                 }
                 ResponseType::WaitComplete => { break; }
                 typ => {
-                    let msg = error_message(resp.r)?;
+                    let error = server_error(resp, conn.token).map_err(|error| guard.failed(error))?;
                     match typ {
                         // This feed has been closed by conn.close().
-                        ResponseType::ClientError if change_feed && msg.contains("not in stream cache") => { break; }
-                        _ => Err(response_error(typ, resp.e, msg))?,
+                        ResponseType::ClientError if change_feed && error.contains("not in stream cache") => { break; }
+                        _ => Err(guard.failed(with_query(response_error(typ, error), &query, &conn)))?,
                     }
                 }
             }
@@ -150,15 +178,75 @@ where
     }
 }
 
-impl Payload<'_> {
-    fn encode(&self, token: u64) -> Result<Vec<u8>> {
-        let bytes = self.to_bytes()?;
-        let data_len = bytes.len();
-        let mut buf = Vec::with_capacity(HEADER_SIZE + data_len);
-        buf.extend_from_slice(&token.to_le_bytes());
-        buf.extend_from_slice(&(data_len as u32).to_le_bytes());
-        buf.extend_from_slice(&bytes);
-        Ok(buf)
+/// Measures of a query, recorded in its span and in the
+/// statistics of the session once the query is done or dropped,
+/// and passed to the interceptors of the session
+struct QueryGuard<'a> {
+    session: Session,
+    span: Span,
+    query: &'a Command,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    started: Instant,
+    rows: u64,
+    batches: u64,
+    error: Option<err::ReqlError>,
+}
+
+impl<'a> QueryGuard<'a> {
+    fn new(
+        session: &Session,
+        span: Span,
+        query: &'a Command,
+        interceptors: Vec<Arc<dyn Interceptor>>,
+    ) -> Self {
+        session.inner.stats.query_started();
+
+        Self {
+            session: session.clone(),
+            span,
+            query,
+            interceptors,
+            started: Instant::now(),
+            rows: 0,
+            batches: 0,
+            error: None,
+        }
+    }
+
+    fn response(&mut self, response_type: ResponseType, value: &RawValue) {
+        self.batches += 1;
+        self.span
+            .record("response_type", field::debug(response_type));
+        for interceptor in &self.interceptors {
+            interceptor.response(self.query, value);
+        }
+    }
+
+    fn failed(&mut self, error: err::ReqlError) -> err::ReqlError {
+        if tracing::enabled!(Level::DEBUG) {
+            let redacted = self.session.inner.redaction.error(&error);
+            self.span
+                .in_scope(|| debug!(kind = error.kind(), error = %redacted, "query failed"));
+        }
+        for interceptor in &self.interceptors {
+            interceptor.error(self.query, &error);
+        }
+        self.error = Some(error.clone());
+        error
+    }
+}
+
+impl Drop for QueryGuard<'_> {
+    fn drop(&mut self) {
+        let latency = self.started.elapsed();
+        self.span.record("rows", self.rows);
+        self.span.record("batches", self.batches);
+        self.span
+            .record("latency_ms", latency.as_secs_f64() * 1000.);
+        self.session
+            .inner
+            .stats
+            .query_finished(latency, self.rows, self.error.as_ref());
     }
 }
 
@@ -197,30 +285,40 @@ impl Connection {
         noreply: bool,
         db_token: &mut u64,
     ) -> Result<(ResponseType, Response)> {
-        let buf = query.encode(self.token)?;
         let mut stream = self.session.inner.stream.lock().await;
+        let mut buf = mem::take(&mut stream.write_buf);
+        query.encode_into(self.token, &mut buf)?;
         let tls_stream = mem::take(&mut stream.tls_stream);
 
-        trace!("sending query; token: {}, payload: {}", self.token, query);
-        if let Some(tcp_stream) = tls_stream {
-            self.tcp_ops(tcp_stream, buf, noreply, db_token).await
+        if tracing::enabled!(Level::TRACE) {
+            let payload = self.session.inner.redaction.query(query);
+            trace!("sending query; token: {}, payload: {}", self.token, payload);
+        }
+        let result = if let Some(tcp_stream) = tls_stream {
+            self.tcp_ops(tcp_stream, &buf, noreply, db_token).await
         } else {
-            self.tcp_ops(stream.stream.clone(), buf, noreply, db_token)
+            self.tcp_ops(stream.stream.clone(), &buf, noreply, db_token)
                 .await
+        };
+        // keep the buffer for the next query, unless a large one grew it
+        if buf.capacity() <= MAX_WRITE_BUF_CAPACITY {
+            stream.write_buf = buf;
         }
+        result
     }
 
     async fn tcp_ops<T>(
         &self,
         mut stream: T,
-        buf: Vec<u8>,
+        buf: &[u8],
         noreply: bool,
         db_token: &mut u64,
     ) -> Result<(ResponseType, Response)>
     where
         T: Unpin + AsyncWrite + AsyncReadExt + AsyncRead + AsyncReadExt,
     {
-        stream.write_all(&buf).await?;
+        stream.write_all(buf).await?;
+        self.session.inner.stats.bytes_sent(buf.len());
         trace!("query sent; token: {}", self.token);
 
         if noreply {
@@ -256,13 +354,16 @@ impl Connection {
         trace!("reading body; token: {}", self.token);
         let mut buf = vec![0u8; len];
         stream.read_exact(&mut buf).await?;
-
-        trace!(
-            "body read; token: {}, db_token: {}, body: {}",
-            self.token,
-            db_token,
-            super::bytes_to_string(&buf),
-        );
+        self.session.inner.stats.bytes_received(HEADER_SIZE + len);
+
+        if tracing::enabled!(Level::TRACE) {
+            trace!(
+                "body read; token: {}, db_token: {}, body: {}",
+                self.token,
+                db_token,
+                self.session.inner.redaction.response(&buf),
+            );
+        }
 
         let resp = serde_json::from_slice::<Response>(&buf)?;
         trace!("response successfully parsed; token: {}", self.token,);
@@ -271,44 +372,193 @@ impl Connection {
             err::ReqlDriverError::Other(format!("unknown response type `{}`", resp.t))
         })?;
 
-        if let Some(error_type) = resp.e {
-            let msg = error_message(resp.r)?;
-            return Err(response_error(response_type, Some(error_type), msg));
+        if resp.e.is_some() {
+            let error = server_error(resp, self.token)?;
+            return Err(response_error(response_type, error));
         }
 
         Ok((response_type, resp))
     }
 }
 
-fn error_message(response: Value) -> Result<String> {
-    let messages = serde_json::from_value::<Vec<String>>(response)?;
-    Ok(messages.join(" "))
+/// Deserialize the documents of an atom straight from the JSON.
+///
+/// The documents are only read one by one after an error,
+/// to tell which one failed.
+fn parse_rows<T: DeserializeOwned>(value: &RawValue) -> Result<Vec<T>> {
+    if !value.get().starts_with('[') {
+        return Ok(vec![serde_json::from_str(value.get())?]);
+    }
+
+    serde_json::from_str::<Vec<T>>(value.get()).map_err(|error| {
+        let failed = serde_json::from_str::<Vec<&RawValue>>(value.get())
+            .ok()
+            .and_then(|rows| {
+                rows.iter().enumerate().find_map(|(row, value)| {
+                    serde_json::from_str::<T>(value.get())
+                        .err()
+                        .map(|error| (row, error))
+                })
+            });
+
+        match failed {
+            Some((row, error)) => err::ReqlDriverError::Json {
+                source: Arc::new(error),
+                row: Some(row),
+            },
+            None => err::ReqlDriverError::Json {
+                source: Arc::new(error),
+                row: None,
+            },
+        }
+        .into()
+    })
 }
 
-fn response_error(
-    response_type: ResponseType,
-    error_type: Option<i32>,
-    msg: String,
-) -> err::ReqlError {
+/// The number of documents of a batch, counting the commas between
+/// them without parsing them: the JSON was validated when the
+/// response was read.
+fn count_rows(value: &RawValue) -> u64 {
+    let json = value.get().as_bytes();
+    let inner = match json {
+        [b'[', inner @ .., b']'] => inner,
+        _ => return 1,
+    };
+    if inner.iter().all(u8::is_ascii_whitespace) {
+        return 0;
+    }
+
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rows = 1;
+
+    for &byte in inner {
+        match (in_string, byte) {
+            (true, _) if escaped => escaped = false,
+            (true, b'\\') => escaped = true,
+            (true, b'"') => in_string = false,
+            (true, _) => {}
+            (false, b'"') => in_string = true,
+            (false, b'[' | b'{') => depth += 1,
+            (false, b']' | b'}') => depth -= 1,
+            (false, b',') if depth == 0 => rows += 1,
+            (false, _) => {}
+        }
+    }
+
+    rows
+}
+
+fn server_error(resp: Response, token: u64) -> Result<err::ServerError> {
+    let messages = serde_json::from_str::<Vec<String>>(resp.r.get())?;
+
+    let context = err::ErrorContext {
+        code: resp.e,
+        token: Some(token),
+        query: None,
+        backtrace: resp.b,
+    };
+
+    Ok(err::ServerError::with_context(messages.join(" "), context))
+}
+
+/// Add the query, rendered with the redaction of the session,
+/// to the error of the server when the session keeps queries in errors.
+fn with_query(mut error: err::ReqlError, query: &Command, conn: &Connection) -> err::ReqlError {
+    if !conn.session.inner.query_in_errors {
+        return error;
+    }
+
+    if let Some(server_error) = error.server_error_mut() {
+        let query = conn.session.inner.redaction.command(&Query(query));
+        server_error.set_query(query);
+    }
+
+    error
+}
+
+fn response_error(response_type: ResponseType, error: err::ServerError) -> err::ReqlError {
     match response_type {
-        ResponseType::ClientError => err::ReqlDriverError::Other(msg).into(),
-        ResponseType::CompileError => err::ReqlError::Compile(msg),
-        ResponseType::RuntimeError => match error_type.map(ErrorType::from_i32).ok_or_else(|| {
-            err::ReqlDriverError::Other(format!("unexpected runtime error: {}", msg))
-        }) {
-            Ok(Some(ErrorType::Internal)) => err::ReqlRuntimeError::Internal(msg).into(),
-            Ok(Some(ErrorType::ResourceLimit)) => err::ReqlRuntimeError::ResourceLimit(msg).into(),
-            Ok(Some(ErrorType::QueryLogic)) => err::ReqlRuntimeError::QueryLogic(msg).into(),
-            Ok(Some(ErrorType::NonExistence)) => err::ReqlRuntimeError::NonExistence(msg).into(),
-            Ok(Some(ErrorType::OpFailed)) => err::ReqlAvailabilityError::OpFailed(msg).into(),
-            Ok(Some(ErrorType::OpIndeterminate)) => {
-                err::ReqlAvailabilityError::OpIndeterminate(msg).into()
+        ResponseType::ClientError => err::ReqlDriverError::Other(error.message().to_owned()).into(),
+        ResponseType::CompileError => err::ReqlError::Compile(error),
+        ResponseType::RuntimeError => match error.code().map(ErrorType::from_i32) {
+            Some(Some(ErrorType::Internal)) => err::ReqlRuntimeError::Internal(error).into(),
+            Some(Some(ErrorType::ResourceLimit)) => {
+                err::ReqlRuntimeError::ResourceLimit(error).into()
+            }
+            Some(Some(ErrorType::QueryLogic)) => err::ReqlRuntimeError::QueryLogic(error).into(),
+            Some(Some(ErrorType::NonExistence)) => {
+                err::ReqlRuntimeError::NonExistence(error).into()
+            }
+            Some(Some(ErrorType::OpFailed)) => err::ReqlAvailabilityError::OpFailed(error).into(),
+            Some(Some(ErrorType::OpIndeterminate)) => {
+                err::ReqlAvailabilityError::OpIndeterminate(error).into()
+            }
+            Some(Some(ErrorType::User)) if error == CONFLICT_ERROR => {
+                err::ReqlRuntimeError::Conflict(error).into()
             }
-            Ok(Some(ErrorType::User)) => err::ReqlRuntimeError::User(msg).into(),
-            Ok(Some(ErrorType::PermissionError)) => err::ReqlRuntimeError::Permission(msg).into(),
-            Err(error) => error.into(),
-            _ => err::ReqlDriverError::Other(format!("unexpected runtime error: {}", msg)).into(),
+            Some(Some(ErrorType::User)) => err::ReqlRuntimeError::User(error).into(),
+            Some(Some(ErrorType::PermissionError)) => {
+                err::ReqlRuntimeError::Permission(error).into()
+            }
+            _ => err::ReqlDriverError::Other(format!("unexpected runtime error: {}", error)).into(),
         },
-        _ => err::ReqlDriverError::Other(format!("unexpected response: {}", msg)).into(),
+        _ => err::ReqlDriverError::Other(format!("unexpected response: {}", error)).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::value::RawValue;
+
+    use ql2::response::{ErrorType, ResponseType};
+
+    use super::{count_rows, parse_rows, response_error, CONFLICT_ERROR};
+    use crate::err::{ErrorContext, ReqlDriverError, ReqlError, ReqlRuntimeError, ServerError};
+
+    #[test]
+    fn test_parse_rows_reports_row() {
+        let raw = |json: &str| RawValue::from_string(json.to_owned()).unwrap();
+        let rows = parse_rows::<u32>(&raw("[1, 2]")).unwrap();
+        assert_eq!(rows, vec![1, 2]);
+        assert_eq!(parse_rows::<u32>(&raw("3")).unwrap(), vec![3]);
+
+        let error = parse_rows::<u32>(&raw(r#"[1, 2, "three"]"#)).unwrap_err();
+        assert!(matches!(
+            error,
+            ReqlError::Driver(ReqlDriverError::Json { row: Some(2), .. })
+        ));
+    }
+
+    #[test]
+    fn test_count_rows() {
+        let raw = |json: &str| RawValue::from_string(json.to_owned()).unwrap();
+
+        assert_eq!(count_rows(&raw(r#"[{"a": [1, 2]}, 3]"#)), 2);
+        assert_eq!(count_rows(&raw(r#"["a,\"]", "[b", {"c": "}"}]"#)), 3);
+        assert_eq!(count_rows(&raw("[ ]")), 0);
+        assert_eq!(count_rows(&raw(r#"{"a": 1}"#)), 1);
+    }
+
+    #[test]
+    fn test_conflict_error() {
+        let user_error = |message: &str| {
+            let context = ErrorContext {
+                code: Some(ErrorType::User as i32),
+                ..Default::default()
+            };
+            let error = ServerError::with_context(message.to_owned(), context);
+            response_error(ResponseType::RuntimeError, error)
+        };
+
+        assert!(matches!(
+            user_error(CONFLICT_ERROR),
+            ReqlError::Runtime(ReqlRuntimeError::Conflict(_))
+        ));
+        assert!(matches!(
+            user_error("conflict"),
+            ReqlError::Runtime(ReqlRuntimeError::User(_))
+        ));
     }
 }
@@ -49,3 +49,13 @@ impl SumArg for Command {
         Some(self)
     }
 }
+
+impl<F, R> SumArg for F
+where
+    F: FnOnce(Command) -> R,
+    R: Into<Command>,
+{
+    fn into_sum_opts(self) -> Option<Command> {
+        Some(Func::from_fn1(self).0)
+    }
+}
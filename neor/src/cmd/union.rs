@@ -1,14 +1,26 @@
 use ql2::term::TermType;
 
-use crate::arguments::{Args, UnionOption};
+use crate::arguments::{Args, Interleave, UnionOption};
 use crate::command_tools::CmdOpts;
 use crate::Command;
 
 pub(crate) fn new(args: impl UnionArg) -> Command {
-    let (args, opts) = args.into_union_opts();
-
-    args.add_to_cmd(Command::new(TermType::Union))
-        .with_opts(opts)
+    let (args, mut opts) = args.into_union_opts();
+    let interleave = match opts.interleave.take() {
+        Some(Interleave::Func(func)) => Some(func),
+        interleave => {
+            opts.interleave = interleave;
+            None
+        }
+    };
+    let command = args
+        .add_to_cmd(Command::new(TermType::Union))
+        .with_opts(opts);
+
+    match interleave {
+        Some(func) => command.with_opt("interleave", func),
+        None => command,
+    }
 }
 
 pub trait UnionArg {
@@ -0,0 +1,92 @@
+use ql2::term::TermType;
+use serde_json::Value;
+
+use crate::err::ReqlDriverError;
+use crate::{args, r, Command, CommandArg, Func, IntoFunc, Result};
+
+/// The message of the error raised when a compare-and-swap update
+/// finds another version, unique so that no user error is mistaken
+/// for a conflict
+pub const CONFLICT_ERROR: &str = "neor:conflict:8d4e6a2c-3b1f-4f7e-9a55-2c9d0e7b1a64";
+
+/// The field holding the version of the documents
+/// updated with [update_if_version](crate::Command::update_if_version)
+pub const VERSION_FIELD: &str = "version";
+
+pub(crate) fn new(
+    parent: &Command,
+    predicate: impl IntoFunc<(Command,)>,
+    patch: impl Into<CommandArg>,
+) -> Command {
+    let patch = r.expr(patch);
+
+    with_conflict(
+        parent,
+        predicate.into_func(),
+        Func::from_fn1(move |_| patch.clone()),
+    )
+}
+
+pub(crate) fn new_versioned(
+    parent: &Command,
+    expected: impl Into<CommandArg>,
+    patch: impl Into<CommandArg>,
+) -> Command {
+    let expected = r.expr(expected);
+    let patch = r.expr(patch);
+
+    let predicate = Func::from_fn1(move |doc| doc.g(VERSION_FIELD).default(0).eq(expected.clone()));
+    let patch = Func::from_fn1(move |doc| {
+        patch.merge(r.object([r.expr(VERSION_FIELD), doc.g(VERSION_FIELD).default(0) + 1]))
+    });
+
+    with_conflict(parent, predicate, patch)
+}
+
+fn with_conflict(parent: &Command, predicate: Func, patch: Func) -> Command {
+    // `update` reports the error of each document in its response,
+    // a conflict can only fail the query for a single document.
+    if parent.term_type() != TermType::Get {
+        let error = ReqlDriverError::Other(
+            "update_if only updates a single document selected with `get`".to_owned(),
+        );
+        let result: Result<Value> = Err(error.into());
+        return result.into();
+    }
+
+    let update = parent.update(Func::from_fn1(move |doc| {
+        r.branch(
+            doc.do_(predicate.clone()),
+            args!(doc.do_(patch.clone()), r.error(CONFLICT_ERROR)),
+        )
+    }));
+
+    // Errors raised inside `update` are only reported in the
+    // response, raise the conflict so that it fails the query.
+    update.do_(Func::from_fn1(|response| {
+        r.branch(
+            response
+                .g("first_error")
+                .default(Value::Null)
+                .eq(CONFLICT_ERROR),
+            args!(r.error(CONFLICT_ERROR), response),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proto::Query;
+    use crate::r;
+
+    #[test]
+    fn test_update_if_selection() {
+        let predicate = |post: crate::Command| post.g("view").eq(10);
+
+        let single = r.table("posts").get(1).update_if(predicate, 1);
+        let many = r.table("posts").update_if(predicate, 1);
+
+        assert!(serde_json::to_string(&Query(&single)).is_ok());
+        assert!(serde_json::to_string(&Query(&many)).is_err());
+    }
+}
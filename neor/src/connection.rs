@@ -16,7 +16,11 @@ use tokio::time;
 use tracing::trace;
 
 use super::cmd::run::Response;
+use crate::arguments::RunOption;
+use crate::intercept::{Interceptor, Interceptors};
+use crate::metrics::{Counters, SessionStats};
 use crate::proto::{Payload, Query};
+use crate::redact::Redaction;
 use crate::types::ServerInfoResponse;
 use crate::{err, r, Result, StaticString};
 
@@ -31,6 +35,10 @@ pub(crate) struct InnerSession {
     pub(crate) token: AtomicU64,
     pub(crate) broken: AtomicBool,
     pub(crate) change_feed: AtomicBool,
+    pub(crate) query_in_errors: bool,
+    pub(crate) redaction: Redaction,
+    pub(crate) interceptors: Interceptors,
+    pub(crate) stats: Counters,
 }
 
 impl InnerSession {
@@ -80,6 +88,8 @@ impl InnerSession {
 #[derive(Debug, Clone)]
 pub struct Session {
     pub(crate) inner: Arc<InnerSession>,
+    /// The options of the queries run by this view of the session
+    pub(crate) defaults: Arc<RunOption>,
 }
 
 impl Session {
@@ -213,16 +223,112 @@ impl Session {
     /// }
     /// ```
     ///
+    /// ## Note
+    ///
+    /// The database is shared by every clone of the session,
+    /// use [with_db](Self::with_db) to change it for some queries only.
+    ///
     /// # Related commands
     /// - [connection](crate::r::connection)
     /// - [close](Self::close)
     /// - [reconnect](Self::reconnect)
+    /// - [with_db](Self::with_db)
     pub async fn use_(&mut self, db_name: impl Into<String>) -> Result<()> {
         *self.inner.db.lock().await = db_name.into().static_string();
 
         Ok(())
     }
 
+    /// Return a view of the session using another default database.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// conn.with_db(db_name) → session
+    /// ```
+    ///
+    /// Where:
+    /// - db_name: `impl Into<String>`
+    ///
+    /// # Description
+    ///
+    /// The view shares the connection to the server with the session,
+    /// but unlike [use_](Self::use_) the database is only changed for
+    /// the queries run with the view. A database given in the
+    /// [RunOption](crate::arguments::RunOption) of a query still wins.
+    ///
+    /// ## Examples
+    ///
+    /// Run the queries of a tenant against its database.
+    ///
+    /// ```
+    /// use neor::{r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let tenant = conn.with_db("tenant_1");
+    ///
+    ///     r.table("posts").run(&tenant).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [with_defaults](Self::with_defaults)
+    /// - [use_](Self::use_)
+    pub fn with_db(&self, db_name: impl Into<String>) -> Session {
+        self.with_defaults(RunOption::default().db(db_name.into()))
+    }
+
+    /// Return a view of the session with default options for its queries.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// conn.with_defaults(run_option) → session
+    /// ```
+    ///
+    /// Where:
+    /// - run_option: [RunOption](crate::arguments::RunOption)
+    ///
+    /// # Description
+    ///
+    /// The view shares the connection to the server with the session.
+    /// The options set in `run_option` replace those of the session,
+    /// and are used by every query run with the view which does not
+    /// set them itself.
+    ///
+    /// ## Examples
+    ///
+    /// Read from the majority of the replicas and write with soft durability.
+    ///
+    /// ```
+    /// use neor::arguments::{Durability, ReadMode, RunOption};
+    /// use neor::{r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let run_option = RunOption::default()
+    ///         .read_mode(ReadMode::Majority)
+    ///         .durability(Durability::Soft);
+    ///     let view = conn.with_defaults(run_option);
+    ///
+    ///     r.table("posts").run(&view).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [with_db](Self::with_db)
+    pub fn with_defaults(&self, run_option: RunOption) -> Session {
+        Session {
+            inner: self.inner.clone(),
+            defaults: Arc::new(run_option.or(&self.defaults)),
+        }
+    }
+
     /// `noreply_wait` ensures that previous queries with
     /// the `noreply` flag have been processed by the server.
     ///
@@ -272,6 +378,77 @@ impl Session {
         Ok(())
     }
 
+    /// Return the statistics of the queries run by the session.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// conn.stats() -> stats
+    /// ```
+    ///
+    /// Where:
+    /// - stats: [SessionStats](crate::metrics::SessionStats)
+    ///
+    /// ## Examples
+    ///
+    /// Count the queries waiting for their response.
+    ///
+    /// ```
+    /// use neor::{r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let stats = conn.stats();
+    ///
+    ///     assert_eq!(stats.in_flight, 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn stats(&self) -> SessionStats {
+        self.inner.stats.snapshot()
+    }
+
+    /// Add an interceptor called around every next query of the session
+    /// and of its clones.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// conn.add_interceptor(interceptor)
+    /// ```
+    ///
+    /// Where:
+    /// - interceptor: impl [Interceptor](crate::intercept::Interceptor)
+    ///
+    /// ## Examples
+    ///
+    /// Log the queries which fail.
+    ///
+    /// ```
+    /// use neor::err::ReqlError;
+    /// use neor::intercept::Interceptor;
+    /// use neor::{r, Command, Result};
+    ///
+    /// struct Audit;
+    ///
+    /// impl Interceptor for Audit {
+    ///     fn error(&self, query: &Command, error: &ReqlError) {
+    ///         eprintln!("{:?} failed: {}", query.term_type(), error);
+    ///     }
+    /// }
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     conn.add_interceptor(Audit);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_interceptor(&self, interceptor: impl Interceptor + 'static) {
+        self.inner.interceptors.push(Arc::new(interceptor));
+    }
+
     /// Return information about the server being used by a connection.
     ///
     /// # Command syntax
@@ -312,7 +489,7 @@ impl Session {
             conn.token,
             typ,
         );
-        let mut vec = serde_json::from_value::<Vec<ServerInfoResponse>>(resp.r)?;
+        let mut vec = serde_json::from_str::<Vec<ServerInfoResponse>>(resp.r.get())?;
         let info = vec
             .pop()
             .ok_or_else(|| err::ReqlDriverError::Other("server info is empty".into()))?;
@@ -428,4 +605,38 @@ impl Drop for Connection {
 pub(crate) struct TcpStreamConnection {
     pub(crate) stream: TcpStream,
     pub(crate) tls_stream: Option<TlsStream<TcpStream>>,
+    /// The buffer the queries are written to, reused between queries
+    pub(crate) write_buf: Vec<u8>,
+}
+
+#[cfg(test)]
+impl Session {
+    /// A session over a local socket, already marked as broken
+    pub(crate) async fn broken() -> Session {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let inner = InnerSession {
+            db: Mutex::new(Cow::Borrowed("test")),
+            stream: Mutex::new(TcpStreamConnection {
+                stream,
+                tls_stream: None,
+                write_buf: Vec::new(),
+            }),
+            channels: DashMap::new(),
+            token: AtomicU64::new(0),
+            broken: AtomicBool::new(true),
+            change_feed: AtomicBool::new(false),
+            query_in_errors: false,
+            redaction: Redaction::default(),
+            interceptors: Interceptors::default(),
+            stats: Default::default(),
+        };
+
+        Session {
+            inner: Arc::new(inner),
+            defaults: Default::default(),
+        }
+    }
 }
@@ -5,6 +5,7 @@ pub(crate) const DEFAULT_AUTHENTICATION_METHOD: &str = "SCRAM-SHA-256";
 pub(crate) const DATA_SIZE: usize = 4;
 pub(crate) const TOKEN_SIZE: usize = 8;
 pub(crate) const HEADER_SIZE: usize = DATA_SIZE + TOKEN_SIZE;
+pub(crate) const MAX_WRITE_BUF_CAPACITY: usize = 1024 * 1024;
 pub(crate) const NANOS_PER_SEC: i128 = 1_000_000_000;
 pub(crate) const NANOS_PER_MSEC: i128 = 1_000_000;
 pub(crate) const TIMEZONE_FORMAT: &str = "[offset_hour sign:mandatory]:[offset_minute]";
@@ -1,15 +1,152 @@
+use std::ops::Deref;
 use std::sync::Arc;
 use std::{error, fmt, io};
 
+use serde_json::Value;
+
 /// The most generic error message in ReQL
 #[derive(Debug, Clone)]
 pub enum ReqlError {
-    Compile(String),
+    Compile(ServerError),
     Runtime(ReqlRuntimeError),
     Driver(ReqlDriverError),
 }
 
-impl error::Error for ReqlError {}
+/// `ReqlError` displays the message of the error it wraps, so its
+/// source is the source of that error, the cause it does not display.
+impl error::Error for ReqlError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Compile(_) => None,
+            Self::Runtime(error) => error.source(),
+            Self::Driver(error) => error.source(),
+        }
+    }
+}
+
+impl ReqlError {
+    /// The context sent by the server with the error,
+    /// if it comes from the server.
+    pub fn server_error(&self) -> Option<&ServerError> {
+        match self {
+            Self::Compile(error) => Some(error),
+            Self::Runtime(error) => error.server_error(),
+            Self::Driver(_) => None,
+        }
+    }
+
+    pub(crate) fn server_error_mut(&mut self) -> Option<&mut ServerError> {
+        match self {
+            Self::Compile(error) => Some(error),
+            Self::Runtime(ReqlRuntimeError::Availability(
+                ReqlAvailabilityError::OpFailed(error)
+                | ReqlAvailabilityError::OpIndeterminate(error),
+            )) => Some(error),
+            Self::Runtime(
+                ReqlRuntimeError::QueryLogic(error)
+                | ReqlRuntimeError::NonExistence(error)
+                | ReqlRuntimeError::ResourceLimit(error)
+                | ReqlRuntimeError::User(error)
+                | ReqlRuntimeError::Internal(error)
+                | ReqlRuntimeError::Permission(error)
+                | ReqlRuntimeError::Conflict(error),
+            ) => Some(error),
+            Self::Driver(_) => None,
+        }
+    }
+
+    /// A short name of the error, like `op_failed` or `io`,
+    /// to label logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Compile(_) => "compile",
+            Self::Runtime(ReqlRuntimeError::QueryLogic(_)) => "query_logic",
+            Self::Runtime(ReqlRuntimeError::NonExistence(_)) => "non_existence",
+            Self::Runtime(ReqlRuntimeError::ResourceLimit(_)) => "resource_limit",
+            Self::Runtime(ReqlRuntimeError::User(_)) => "user",
+            Self::Runtime(ReqlRuntimeError::Internal(_)) => "internal",
+            Self::Runtime(ReqlRuntimeError::Permission(_)) => "permission",
+            Self::Runtime(ReqlRuntimeError::Conflict(_)) => "conflict",
+            Self::Runtime(ReqlRuntimeError::Availability(ReqlAvailabilityError::OpFailed(_))) => {
+                "op_failed"
+            }
+            Self::Runtime(ReqlRuntimeError::Availability(
+                ReqlAvailabilityError::OpIndeterminate(_),
+            )) => "op_indeterminate",
+            Self::Driver(ReqlDriverError::Auth(_)) => "auth",
+            Self::Driver(ReqlDriverError::ConnectionBroken) => "connection_broken",
+            Self::Driver(ReqlDriverError::ConnectionLocked) => "connection_locked",
+            Self::Driver(ReqlDriverError::Io(_)) => "io",
+            Self::Driver(ReqlDriverError::Json { .. }) => "json",
+            Self::Driver(ReqlDriverError::Time(_)) => "time",
+            Self::Driver(ReqlDriverError::Tls(_)) => "tls",
+            Self::Driver(ReqlDriverError::DriverUrl(_)) => "driver_url",
+            Self::Driver(ReqlDriverError::Other(_)) => "other",
+        }
+    }
+
+    /// The token of the query which failed, if it comes from the server.
+    pub fn token(&self) -> Option<u64> {
+        self.server_error().and_then(ServerError::token)
+    }
+
+    /// Whether the query may succeed if it is run again, because
+    /// the error comes from the availability of the cluster or from
+    /// the connection. Errors of the query itself, including
+    /// `ResourceLimit` errors, are not transient.
+    ///
+    /// After a `ConnectionBroken` error, the query can only succeed
+    /// on a new session, the broken one fails every query.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Runtime(ReqlRuntimeError::Availability(_)) => true,
+            Self::Driver(ReqlDriverError::ConnectionBroken) => true,
+            Self::Driver(ReqlDriverError::Io(error)) => is_transient_io(error.kind()),
+            _ => false,
+        }
+    }
+
+    /// Whether the query may have been applied although it failed,
+    /// like an `OpIndeterminate` error or a connection lost while
+    /// waiting for the response.
+    pub fn is_indeterminate(&self) -> bool {
+        match self {
+            Self::Runtime(ReqlRuntimeError::Availability(
+                ReqlAvailabilityError::OpIndeterminate(_),
+            )) => true,
+            Self::Driver(ReqlDriverError::ConnectionBroken) => true,
+            Self::Driver(ReqlDriverError::Io(error)) => {
+                is_transient_io(error.kind())
+                    && !matches!(
+                        error.kind(),
+                        io::ErrorKind::ConnectionRefused | io::ErrorKind::NotConnected
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether any query, including a write, can safely be run again:
+    /// the error is transient and the query was certainly not applied,
+    /// like an `OpFailed` error.
+    pub fn is_retryable(&self) -> bool {
+        self.is_transient() && !self.is_indeterminate()
+    }
+}
+
+fn is_transient_io(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::UnexpectedEof
+    )
+}
 
 impl fmt::Display for ReqlError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -21,6 +158,122 @@ impl fmt::Display for ReqlError {
     }
 }
 
+/// The message of an error raised by the server, with its context
+///
+/// It compares with and derefs to the message, so that it can be
+/// used as a `str`. The rendered query is only kept when the session
+/// was opened with
+/// [query_in_errors](crate::cmd::connect::ConnectionCommand::query_in_errors).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerError {
+    message: String,
+    // boxed to keep `ReqlError`, and so `Command`, small
+    context: Box<ErrorContext>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ErrorContext {
+    pub(crate) code: Option<i32>,
+    pub(crate) token: Option<u64>,
+    pub(crate) query: Option<String>,
+    pub(crate) backtrace: Option<Value>,
+}
+
+impl ServerError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            context: Default::default(),
+        }
+    }
+
+    pub(crate) fn with_context(message: String, context: ErrorContext) -> Self {
+        Self {
+            message,
+            context: Box::new(context),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The `ErrorType` code of a runtime error.
+    pub fn code(&self) -> Option<i32> {
+        self.context.code
+    }
+
+    /// The token of the query which failed.
+    pub fn token(&self) -> Option<u64> {
+        self.context.token
+    }
+
+    /// The query which failed, as sent to the server.
+    pub fn query(&self) -> Option<&str> {
+        self.context.query.as_deref()
+    }
+
+    /// The path to the term which failed in the query.
+    pub fn backtrace(&self) -> Option<&Value> {
+        self.context.backtrace.as_ref()
+    }
+
+    pub(crate) fn set_query(&mut self, query: String) {
+        self.context.query.get_or_insert(query);
+    }
+}
+
+impl Deref for ServerError {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.message
+    }
+}
+
+impl PartialEq<str> for ServerError {
+    fn eq(&self, other: &str) -> bool {
+        self.message == other
+    }
+}
+
+impl PartialEq<&str> for ServerError {
+    fn eq(&self, other: &&str) -> bool {
+        self.message == *other
+    }
+}
+
+impl PartialEq<String> for ServerError {
+    fn eq(&self, other: &String) -> bool {
+        &self.message == other
+    }
+}
+
+impl From<String> for ServerError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for ServerError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(token) = self.context.token {
+            write!(f, " (token {})", token)?;
+        }
+        if let Some(query) = &self.context.query {
+            write!(f, " in query {}", query)?;
+        }
+        Ok(())
+    }
+}
+
 /// The parent class of all runtime errors
 ///
 /// All errors on the server unrelated to compilation. Programs may use this to catch any runtime
@@ -28,13 +281,37 @@ impl fmt::Display for ReqlError {
 #[derive(Debug, Clone)]
 pub enum ReqlRuntimeError {
     /// The query contains a logical impossibility, such as adding a number to a string.
-    QueryLogic(String),
-    NonExistence(String),
-    ResourceLimit(String),
-    User(String),
-    Internal(String),
+    QueryLogic(ServerError),
+    NonExistence(ServerError),
+    ResourceLimit(ServerError),
+    User(ServerError),
+    Internal(ServerError),
     Availability(ReqlAvailabilityError),
-    Permission(String),
+    Permission(ServerError),
+    /// A compare-and-swap update found another version of the document,
+    /// see [update_if_version](crate::Command::update_if_version).
+    Conflict(ServerError),
+}
+
+impl error::Error for ReqlRuntimeError {}
+
+impl ReqlRuntimeError {
+    /// The context sent by the server with the error.
+    pub fn server_error(&self) -> Option<&ServerError> {
+        match self {
+            Self::QueryLogic(error)
+            | Self::NonExistence(error)
+            | Self::ResourceLimit(error)
+            | Self::User(error)
+            | Self::Internal(error)
+            | Self::Permission(error)
+            | Self::Conflict(error) => Some(error),
+            Self::Availability(
+                ReqlAvailabilityError::OpFailed(error)
+                | ReqlAvailabilityError::OpIndeterminate(error),
+            ) => Some(error),
+        }
+    }
 }
 
 impl From<ReqlRuntimeError> for ReqlError {
@@ -53,6 +330,7 @@ impl fmt::Display for ReqlRuntimeError {
             Self::Internal(msg) => write!(f, "internal error; {}", msg),
             Self::Availability(msg) => write!(f, "availability error; {}", msg),
             Self::Permission(msg) => write!(f, "permission error; {}", msg),
+            Self::Conflict(msg) => write!(f, "conflict error; {}", msg),
         }
     }
 }
@@ -64,10 +342,12 @@ impl fmt::Display for ReqlRuntimeError {
 /// children.
 #[derive(Debug, Clone)]
 pub enum ReqlAvailabilityError {
-    OpFailed(String),
-    OpIndeterminate(String),
+    OpFailed(ServerError),
+    OpIndeterminate(ServerError),
 }
 
+impl error::Error for ReqlAvailabilityError {}
+
 impl From<ReqlAvailabilityError> for ReqlError {
     fn from(err: ReqlAvailabilityError) -> ReqlError {
         ReqlRuntimeError::Availability(err).into()
@@ -93,14 +373,31 @@ pub enum ReqlDriverError {
     Auth(String),
     ConnectionBroken,
     ConnectionLocked,
-    Io(io::ErrorKind, String),
-    Json(Arc<serde_json::Error>),
+    Io(Arc<io::Error>),
+    /// A response could not be deserialized, `row` being the position
+    /// of the document which failed when it is part of a sequence.
+    Json {
+        source: Arc<serde_json::Error>,
+        row: Option<usize>,
+    },
     Other(String),
     Time(String),
     Tls(String),
     DriverUrl(String),
 }
 
+/// The errors of other crates are not part of the message,
+/// they are the source of the error.
+impl error::Error for ReqlDriverError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error.as_ref()),
+            Self::Json { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
 impl From<ReqlDriverError> for ReqlError {
     fn from(err: ReqlDriverError) -> ReqlError {
         ReqlError::Driver(err)
@@ -116,8 +413,9 @@ impl fmt::Display for ReqlDriverError {
                 f,
                 "another query is running a changefeed on this connection"
             ),
-            Self::Io(_, error) => write!(f, "{}", error),
-            Self::Json(error) => write!(f, "{}", error),
+            Self::Io(error) => write!(f, "io error ({})", error.kind()),
+            Self::Json { row: Some(row), .. } => write!(f, "invalid json at row {}", row),
+            Self::Json { row: None, .. } => write!(f, "invalid json"),
             Self::Other(msg) => write!(f, "{}", msg),
             Self::Time(error) => write!(f, "{}", error),
             Self::Tls(error) => write!(f, "{}", error),
@@ -128,13 +426,17 @@ impl fmt::Display for ReqlDriverError {
 
 impl From<io::Error> for ReqlError {
     fn from(err: io::Error) -> ReqlError {
-        ReqlDriverError::Io(err.kind(), err.to_string()).into()
+        ReqlDriverError::Io(Arc::new(err)).into()
     }
 }
 
 impl From<serde_json::Error> for ReqlError {
     fn from(err: serde_json::Error) -> ReqlError {
-        ReqlDriverError::Json(Arc::new(err)).into()
+        ReqlDriverError::Json {
+            source: Arc::new(err),
+            row: None,
+        }
+        .into()
     }
 }
 
@@ -173,3 +475,70 @@ impl From<url::ParseError> for ReqlError {
         ReqlDriverError::DriverUrl(err.to_string()).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+
+    use super::{ErrorContext, ReqlDriverError, ReqlError, ReqlRuntimeError, ServerError};
+
+    #[test]
+    fn test_io_error_source() {
+        let error = ReqlError::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+        let source = error.source().unwrap();
+
+        assert_eq!(source.to_string(), "reset");
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::ConnectionReset
+        );
+    }
+
+    #[test]
+    fn test_json_error_row() {
+        let source = serde_json::from_str::<u32>("\"text\"").unwrap_err();
+        let error: ReqlError = ReqlDriverError::Json {
+            source: source.into(),
+            row: Some(3),
+        }
+        .into();
+
+        assert_eq!(error.to_string(), "client error; invalid json at row 3");
+        assert!(error.source().unwrap().is::<serde_json::Error>());
+    }
+
+    #[test]
+    fn test_error_chain_messages() {
+        let error = ReqlError::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+        let mut messages = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            messages.push(error.to_string());
+            source = error.source();
+        }
+
+        assert_eq!(
+            messages,
+            ["client error; io error (connection reset)", "reset"]
+        );
+    }
+
+    #[test]
+    fn test_server_error_context() {
+        let context = ErrorContext {
+            code: Some(1_000_000),
+            token: Some(7),
+            ..Default::default()
+        };
+        let server_error = ServerError::with_context("Error".to_owned(), context);
+        let error: ReqlError = ReqlRuntimeError::User(server_error).into();
+
+        assert_eq!(error.token(), Some(7));
+        assert!(error.server_error().unwrap() == "Error");
+        assert_eq!(
+            error.to_string(),
+            "runtime error; user error; Error (token 7)"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Observe and rewrite the queries run by a session.
+//!
+//! An [Interceptor] is called before every query is sent, with the
+//! query and its [RunOption], and after each response or error.
+//! Interceptors are added to a session with
+//! [interceptor](crate::cmd::connect::ConnectionCommand::interceptor)
+//! or [add_interceptor](crate::Session::add_interceptor), and are
+//! called in the order they were added.
+//!
+//! ```
+//! use neor::arguments::{ReadMode, RunOption};
+//! use neor::err::{ReqlDriverError, ReqlError};
+//! use neor::intercept::Interceptor;
+//! use neor::{r, Command, Result};
+//!
+//! struct ReadOnly;
+//!
+//! impl Interceptor for ReadOnly {
+//!     fn before(&self, query: &mut Command, options: &mut RunOption) -> Result<()> {
+//!         if query.is_write() {
+//!             return Err(ReqlDriverError::Other("writes are not allowed".to_owned()).into());
+//!         }
+//!         options.read_mode = Some(ReadMode::Majority);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().interceptor(ReadOnly).connect().await?;
+//!
+//!     r.table("posts").run(&conn).await?;
+//!     assert!(r.table("posts").delete(()).run(&conn).await.is_err());
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use serde_json::value::RawValue;
+
+use crate::arguments::RunOption;
+use crate::err::ReqlError;
+use crate::{Command, Result};
+
+/// A hook called around every query run by a session
+pub trait Interceptor: Send + Sync {
+    /// Called before the query is sent, it can change the query and
+    /// its options. An error fails the query without sending it.
+    fn before(&self, query: &mut Command, options: &mut RunOption) -> Result<()> {
+        let _ = (query, options);
+        Ok(())
+    }
+
+    /// Called with the JSON of each response, a whole result or
+    /// a batch of a sequence. It is not parsed, so that only the
+    /// interceptors reading it pay for it.
+    fn response(&self, query: &Command, response: &RawValue) {
+        let _ = (query, response);
+    }
+
+    /// Called when the query fails.
+    fn error(&self, query: &Command, error: &ReqlError) {
+        let _ = (query, error);
+    }
+}
+
+/// Share an interceptor, to read its state once added to a session.
+impl<I: Interceptor + ?Sized> Interceptor for Arc<I> {
+    fn before(&self, query: &mut Command, options: &mut RunOption) -> Result<()> {
+        (**self).before(query, options)
+    }
+
+    fn response(&self, query: &Command, response: &RawValue) {
+        (**self).response(query, response)
+    }
+
+    fn error(&self, query: &Command, error: &ReqlError) {
+        (**self).error(query, error)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Interceptors(RwLock<Vec<Arc<dyn Interceptor>>>);
+
+impl Interceptors {
+    pub(crate) fn push(&self, interceptor: Arc<dyn Interceptor>) {
+        self.0
+            .write()
+            .unwrap_or_else(|error| error.into_inner())
+            .push(interceptor);
+    }
+
+    /// The interceptors of a query, so that interceptors added while
+    /// it runs are only called for the next queries.
+    pub(crate) fn snapshot(&self) -> Vec<Arc<dyn Interceptor>> {
+        self.0
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+}
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Interceptors({})", self.snapshot().len())
+    }
+}
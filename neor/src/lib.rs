@@ -7,9 +7,9 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use arguments::Permission;
 use err::ReqlError;
-use types::{Binary, DateTime, GeoJson};
+use types::{Binary, DateTime, GeoJson, Month, Weekday};
 
-pub use cmd::func::Func;
+pub use cmd::func::{Func, IntoFunc};
 pub use command_tools::CommandArg;
 pub use connection::*;
 pub use proto::Command;
@@ -18,10 +18,18 @@ mod command_tools;
 mod constants;
 mod proto;
 
+pub mod admin;
 pub mod arguments;
+pub mod backup;
 pub mod cmd;
 pub mod connection;
 pub mod err;
+pub mod intercept;
+pub mod metrics;
+pub mod migrate;
+pub mod redact;
+pub mod retry;
+pub mod template;
 pub mod types;
 
 pub type Result<T> = std::result::Result<T, ReqlError>;
@@ -905,7 +913,7 @@ impl r {
     ///
     /// Where:
     /// - base: `impl Serialize` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -988,7 +996,7 @@ impl r {
     /// - [avg](Self::avg)
     /// - [min](Self::min)
     /// - [max](Self::max)
-    pub fn reduce(&self, sequence: Command, func: Func) -> Command {
+    pub fn reduce(&self, sequence: Command, func: impl IntoFunc<(Command, Command)>) -> Command {
         sequence.reduce(func)
     }
 
@@ -1011,7 +1019,7 @@ impl r {
     ///
     /// Where:
     /// - value: `impl Serialize`
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence, binary, string, object, query_cmd: [Command](crate::Command)
     ///
     /// # Description
@@ -1156,7 +1164,7 @@ impl r {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -1258,7 +1266,7 @@ impl r {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - sequence: [Command](crate::Command)
     ///
     /// # Description
@@ -1363,7 +1371,7 @@ impl r {
     ///
     /// Where:
     /// - field: `&str` | `String` | [Command](crate::Command)
-    /// - func: [Func](crate::Func)
+    /// - func: [Func](crate::Func) | [closure](crate::IntoFunc)
     /// - options: [MinOption](crate::arguments::MinOption)
     /// - sequence: [Command](crate::Command)
     ///
@@ -4395,18 +4403,25 @@ impl r {
     ///
     /// ```text
     /// r.http(string) → value
-    /// r.http(args!(string, options)) → value
+    /// r.http(args!(string, options)) → value | stream
     /// ```
     ///
     /// Where:
     /// - string: `impl Into<String>`
-    /// - options: `impl Serialize`
+    /// - options: [HttpOption](crate::arguments::HttpOption)
     ///
     /// # Description
     ///
     /// The return type depends on the `result_format` option,
     /// which checks the `Content-Type` of the response by default.
     ///
+    /// When the `page` option is set, `http` returns a stream
+    /// with the results of every page. Like any stream,
+    /// [run](crate::Command::run) only returns its first batch:
+    /// read every page with [build_query](crate::Command::build_query),
+    /// or with `run` after `coerce_to("array")`, which holds
+    /// every page in memory.
+    ///
     /// See [External API access](https://rethinkdb.com/docs/external-api-access/)
     /// for more informations
     ///
@@ -4439,17 +4454,15 @@ impl r {
     /// Perform request with parameters.
     ///
     /// ```
+    /// use neor::arguments::HttpOption;
     /// use neor::{args, r, Result};
     /// use serde_json::json;
     ///
     /// async fn example() -> Result<()> {
     ///     let conn = r.connection().connect().await?;
+    ///     let http_option = HttpOption::default().params(json!({"user": 1}));
     ///
-    ///     let response = r.http(args!("http://httpbin.org/get", json!({
-    ///             "params": {
-    ///                 "user": 1
-    ///             }
-    ///         })))
+    ///     let response = r.http(args!("http://httpbin.org/get", http_option))
     ///         .run(&conn)
     ///         .await?;
     ///
@@ -4464,19 +4477,17 @@ impl r {
     /// Perform a `POST` request with accompanying data.
     ///
     /// ```
+    /// use neor::arguments::{HttpMethod, HttpOption};
     /// use neor::{args, r, Result};
     /// use serde_json::json;
     ///
     /// async fn example() -> Result<()> {
     ///     let conn = r.connection().connect().await?;
+    ///     let http_option = HttpOption::default()
+    ///         .method(HttpMethod::Post)
+    ///         .data(json!({"player": "Moussa", "game": "AURION"}));
     ///
-    ///     let response = r.http(args!("http://httpbin.org/get", json!({
-    ///             "method": "method",
-    ///             "data": {
-    ///                 "player": "Moussa",
-    ///                 "game": "AURION"
-    ///             }
-    ///         })))
+    ///     let response = r.http(args!("http://httpbin.org/post", http_option))
     ///         .run(&conn)
     ///         .await?;
     ///
@@ -4491,28 +4502,81 @@ impl r {
     /// Perform a GitHub search and collect up to 3 pages of results.
     ///
     /// ```
+    /// use futures::TryStreamExt;
+    /// use neor::arguments::{HttpOption, Page};
     /// use neor::{args, r, Result};
-    /// use serde_json::json;
     ///
     /// async fn example() -> Result<()> {
     ///     let conn = r.connection().connect().await?;
+    ///     let http_option = HttpOption::default()
+    ///         .page(Page::LinkNext)
+    ///         .page_limit(3);
     ///
-    ///     let response = r.http(args!("https://api.github.com/search/code?q=addClass+user:mozilla", json!({
-    ///             "page": "link-next",
-    ///             "page_limit": 3
-    ///         })))
+    ///     let mut query = r
+    ///         .http(args!("https://api.github.com/search/code?q=addClass+user:mozilla", http_option))
+    ///         .build_query(&conn);
+    ///
+    ///     while let Some(results) = query.try_next().await? {
+    ///         assert!(results.is_array());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Read the same pages as a single array with `run`.
+    ///
+    /// ```
+    /// use neor::arguments::{HttpOption, Page};
+    /// use neor::{args, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let http_option = HttpOption::default()
+    ///         .page(Page::LinkNext)
+    ///         .page_limit(3);
+    ///
+    ///     let response = r
+    ///         .http(args!("https://api.github.com/search/code?q=addClass+user:mozilla", http_option))
+    ///         .coerce_to("array")
     ///         .run(&conn)
     ///         .await?;
     ///
     ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Follow the `next` URL given in the body of each page.
+    ///
+    /// ```
+    /// use futures::TryStreamExt;
+    /// use neor::arguments::{HttpOption, Page};
+    /// use neor::{args, func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let http_option = HttpOption::default()
+    ///         .page(Page::Func(func!(|info| info.g("body").g("next"))))
+    ///         .page_limit(5);
+    ///
+    ///     let mut query = r
+    ///         .http(args!("https://example.com/api/items", http_option))
+    ///         .build_query(&conn);
+    ///
+    ///     while let Some(results) = query.try_next().await? {
+    ///         assert!(results.is_array());
+    ///     }
     ///     
     ///     Ok(())
     /// }
     /// ```
-    pub fn http<T>(&self, args: impl cmd::http::HttpArg<T>) -> Command
-    where
-        T: Serialize,
-    {
+    pub fn http(&self, args: impl cmd::http::HttpArg) -> Command {
         cmd::http::new(args)
     }
 
@@ -5198,6 +5262,260 @@ impl r {
         cmd::grant::new(username, permission)
     }
 
+    /// Revoke all the global permissions of a user account.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.revoke(username) → response
+    /// ```
+    ///
+    /// Where:
+    /// - username: `impl Into<String>`
+    /// - response: [GrantResponse](crate::types::GrantResponse)
+    ///
+    /// # Description
+    ///
+    /// This is [grant](Self::grant) with the `read`, `write`, `config`
+    /// and `connect` permissions set to `null`. Permissions granted
+    /// on databases and tables are left untouched,
+    /// see [revoke](crate::Command::revoke).
+    ///
+    /// ## Examples
+    ///
+    /// Remove the global permissions of `monitor`.
+    ///
+    /// ```
+    /// use neor::types::GrantResponse;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let response: GrantResponse = r.revoke("monitor")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.granted == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn revoke(&self, username: impl Into<String>) -> Command {
+        cmd::revoke::new(username, true)
+    }
+
+    /// List the permissions of a user account in every scope.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.permissions_for(username) → array
+    /// ```
+    ///
+    /// Where:
+    /// - username: `impl Into<String>`
+    /// - array: `Vec<`[UserPermission](crate::admin::UserPermission)`>`
+    ///
+    /// # Description
+    ///
+    /// Each row comes from the `permissions` system table,
+    /// its [scope](crate::admin::UserPermission::scope) tells
+    /// whether it applies globally, to a database or to a table.
+    ///
+    /// ## Examples
+    ///
+    /// Find the tables `alima` has permissions on.
+    ///
+    /// ```
+    /// use neor::admin::{PermissionScope, UserPermission};
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let permissions: Vec<UserPermission> = r.permissions_for("alima")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///     let tables = permissions
+    ///         .iter()
+    ///         .filter(|permission| matches!(permission.scope(), PermissionScope::Table { .. }))
+    ///         .count();
+    ///
+    ///     assert!(tables > 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn permissions_for(&self, username: impl Into<String>) -> Command {
+        admin::permissions().filter(serde_json::json!({ "user": username.into() }))
+    }
+
+    /// Create a user account.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.user_create(username, password) → response
+    /// ```
+    ///
+    /// Where:
+    /// - username, password: `impl Into<String>`
+    /// - response: [MutationResponse](crate::types::MutationResponse)
+    ///
+    /// # Description
+    ///
+    /// The account has no permissions until they are [granted](Self::grant).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use neor::types::MutationResponse;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let response: MutationResponse = r.user_create("alima", "secret")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.inserted == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [user_drop](Self::user_drop)
+    /// - [user_set_password](Self::user_set_password)
+    /// - [user_list](Self::user_list)
+    pub fn user_create(&self, username: impl Into<String>, password: impl Into<String>) -> Command {
+        admin::users().insert(serde_json::json!({
+            "id": username.into(),
+            "password": password.into(),
+        }))
+    }
+
+    /// Delete a user account, along with its permissions.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.user_drop(username) → response
+    /// ```
+    ///
+    /// Where:
+    /// - username: `impl Into<String>`
+    /// - response: [MutationResponse](crate::types::MutationResponse)
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use neor::types::MutationResponse;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let response: MutationResponse = r.user_drop("alima")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.deleted == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn user_drop(&self, username: impl Into<String>) -> Command {
+        admin::users().get(username.into()).delete(())
+    }
+
+    /// Change the password of a user account.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.user_set_password(username, password) → response
+    /// ```
+    ///
+    /// Where:
+    /// - username, password: `impl Into<String>`
+    /// - response: [MutationResponse](crate::types::MutationResponse)
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use neor::types::MutationResponse;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let response: MutationResponse = r.user_set_password("alima", "n3w-s3cret")
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(response.replaced == 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn user_set_password(
+        &self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Command {
+        admin::users()
+            .get(username.into())
+            .update(serde_json::json!({ "password": password.into() }))
+    }
+
+    /// List the user accounts.
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.user_list() → array
+    /// ```
+    ///
+    /// Where:
+    /// - array: `Vec<`[User](crate::admin::User)`>`
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use neor::admin::User;
+    /// use neor::{r, Converter, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///
+    ///     let users: Vec<User> = r.user_list()
+    ///         .run(&conn)
+    ///         .await?
+    ///         .unwrap()
+    ///         .parse()?;
+    ///
+    ///     assert!(users.iter().any(|user| user.id == "admin"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn user_list(&self) -> Command {
+        admin::users()
+    }
+
     /// Wait for a table or all the tables in a database to be ready.
     ///
     /// # Command syntax
@@ -5397,6 +5715,759 @@ impl r {
         cmd::index::new(args)
     }
 
+    /// Returns the currently visited document.
+    ///
+    /// # Command syntax
+    /// ```text
+    /// r.row()
+    /// ```
+    ///
+    /// # Description
+    ///
+    /// `r.row` is a shortcut for a one-argument function: when it is used in
+    /// the argument of a command taking a function (such as `filter`, `map`,
+    /// `order_by` or `update`), that argument is sent as a function whose
+    /// parameter is the visited document.
+    ///
+    /// ## Note
+    ///
+    /// `r.row` cannot be used inside nested functions, where it would be
+    /// ambiguous. Running such a query fails with a `ReqlDriverError`;
+    /// use [func](crate::func) instead.
+    ///
+    /// ## Example
+    ///
+    /// Get all users older than 18.
+    ///
+    /// ```
+    /// use neor::{r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(r.row().g("age").gt(18))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn row(&self) -> Command {
+        Command::new(ql2::term::TermType::ImplicitVar)
+    }
+
+    /// Returns a parameter of a query template.
+    ///
+    /// # Command syntax
+    /// ```text
+    /// r.param(name)
+    /// ```
+    ///
+    /// Where:
+    /// - name: `impl Into<Cow<'static, str>>`
+    ///
+    /// # Description
+    ///
+    /// The query holding the parameter is serialized once by a
+    /// [Template](crate::template::Template), and its value is given
+    /// when the template is [bound](crate::template::Template::bind).
+    ///
+    /// ## Note
+    ///
+    /// Running a query with a parameter without a template fails
+    /// with a `ReqlDriverError`.
+    ///
+    /// ## Example
+    ///
+    /// Get the title of a post by its id.
+    ///
+    /// ```
+    /// use neor::template::Template;
+    /// use neor::{r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let query = r.table("posts").get(r.param("id")).g("title");
+    ///     let template = Template::new(&query)?;
+    ///
+    ///     let response = template.bind().param("id", 1).query()?.run(&conn).await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn param(&self, name: impl Into<Cow<'static, str>>) -> Command {
+        template::param(name)
+    }
+
+    /// Monday, the day of week 1 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.monday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Monday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.monday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn monday(&self) -> Command {
+        Weekday::Monday.into()
+    }
+
+    /// Tuesday, the day of week 2 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.tuesday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Tuesday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.tuesday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn tuesday(&self) -> Command {
+        Weekday::Tuesday.into()
+    }
+
+    /// Wednesday, the day of week 3 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.wednesday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Wednesday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.wednesday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn wednesday(&self) -> Command {
+        Weekday::Wednesday.into()
+    }
+
+    /// Thursday, the day of week 4 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.thursday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Thursday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.thursday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn thursday(&self) -> Command {
+        Weekday::Thursday.into()
+    }
+
+    /// Friday, the day of week 5 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.friday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Friday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.friday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn friday(&self) -> Command {
+        Weekday::Friday.into()
+    }
+
+    /// Saturday, the day of week 6 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.saturday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Saturday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.saturday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn saturday(&self) -> Command {
+        Weekday::Saturday.into()
+    }
+
+    /// Sunday, the day of week 7 returned by
+    /// [day_of_week](crate::Command::day_of_week).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.sunday() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born on a Sunday.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").day_of_week().eq(r.sunday())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Weekday](crate::types::Weekday)
+    pub fn sunday(&self) -> Command {
+        Weekday::Sunday.into()
+    }
+
+    /// January, the month 1 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.january() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in January.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.january())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn january(&self) -> Command {
+        Month::January.into()
+    }
+
+    /// February, the month 2 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.february() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in February.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.february())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn february(&self) -> Command {
+        Month::February.into()
+    }
+
+    /// March, the month 3 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.march() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in March.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.march())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn march(&self) -> Command {
+        Month::March.into()
+    }
+
+    /// April, the month 4 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.april() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in April.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.april())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn april(&self) -> Command {
+        Month::April.into()
+    }
+
+    /// May, the month 5 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.may() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in May.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.may())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn may(&self) -> Command {
+        Month::May.into()
+    }
+
+    /// June, the month 6 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.june() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in June.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.june())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn june(&self) -> Command {
+        Month::June.into()
+    }
+
+    /// July, the month 7 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.july() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in July.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.july())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn july(&self) -> Command {
+        Month::July.into()
+    }
+
+    /// August, the month 8 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.august() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in August.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.august())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn august(&self) -> Command {
+        Month::August.into()
+    }
+
+    /// September, the month 9 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.september() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in September.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.september())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn september(&self) -> Command {
+        Month::September.into()
+    }
+
+    /// October, the month 10 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.october() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in October.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.october())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn october(&self) -> Command {
+        Month::October.into()
+    }
+
+    /// November, the month 11 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.november() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in November.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.november())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn november(&self) -> Command {
+        Month::November.into()
+    }
+
+    /// December, the month 12 returned by
+    /// [month](crate::Command::month).
+    ///
+    /// # Command syntax
+    ///
+    /// ```text
+    /// r.december() → number
+    /// ```
+    ///
+    /// ## Examples
+    ///
+    /// Return the users born in December.
+    ///
+    /// ```
+    /// use neor::{func, r, Result};
+    ///
+    /// async fn example() -> Result<()> {
+    ///     let conn = r.connection().connect().await?;
+    ///     let response = r.table("users")
+    ///         .filter(func!(|user| user.g("birthdate").month().eq(r.december())))
+    ///         .run(&conn)
+    ///         .await?;
+    ///
+    ///     assert!(response.is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Related commands
+    /// - [Month](crate::types::Month)
+    pub fn december(&self) -> Command {
+        Month::December.into()
+    }
+
     /// max_val are used with some commands such as `between`
     /// to specify absolute upper bounds.
     ///
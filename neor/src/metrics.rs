@@ -0,0 +1,234 @@
+//! Statistics of the queries run by a session.
+//!
+//! Every session counts its queries, returned by
+//! [stats](crate::Session::stats). With the `metrics` feature,
+//! the same measures are also reported to the [Recorder] set with
+//! [set_recorder], to export them to Prometheus, OpenTelemetry
+//! or any other metrics backend.
+//!
+//! ```
+//! use neor::{r, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     r.db_list().run(&conn).await?;
+//!
+//!     let stats = conn.stats();
+//!     assert_eq!(stats.queries, 1);
+//!     assert_eq!(stats.in_flight, 0);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "metrics")]
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::err::ReqlError;
+
+/// Histogram of the time taken by the queries, in seconds
+#[cfg(feature = "metrics")]
+pub const QUERY_DURATION: &str = "neor_query_duration_seconds";
+
+/// Counter of the failed queries, labelled by
+/// [kind](crate::err::ReqlError::kind) of error
+#[cfg(feature = "metrics")]
+pub const QUERY_ERRORS: &str = "neor_query_errors_total";
+
+/// Counter of the bytes written to the server
+#[cfg(feature = "metrics")]
+pub const BYTES_SENT: &str = "neor_bytes_sent_total";
+
+/// Counter of the bytes read from the server
+#[cfg(feature = "metrics")]
+pub const BYTES_RECEIVED: &str = "neor_bytes_received_total";
+
+/// Gauge of the queries waiting for their response
+#[cfg(feature = "metrics")]
+pub const QUERIES_IN_FLIGHT: &str = "neor_queries_in_flight";
+
+/// A metrics backend, receiving the measures of every session
+#[cfg(feature = "metrics")]
+pub trait Recorder: Send + Sync {
+    fn record_histogram(&self, name: &'static str, value: f64);
+
+    fn increment_counter(&self, name: &'static str, labels: &[(&'static str, &str)], value: u64);
+
+    fn increment_gauge(&self, name: &'static str, value: f64);
+}
+
+#[cfg(feature = "metrics")]
+static RECORDER: OnceLock<Box<dyn Recorder>> = OnceLock::new();
+
+/// Set the recorder of the process, which can only be set once.
+#[cfg(feature = "metrics")]
+pub fn set_recorder(recorder: impl Recorder + 'static) -> crate::Result<()> {
+    RECORDER.set(Box::new(recorder)).map_err(|_| {
+        crate::err::ReqlDriverError::Other("a metrics recorder is already set".to_owned()).into()
+    })
+}
+
+#[cfg(feature = "metrics")]
+fn recorder() -> Option<&'static dyn Recorder> {
+    RECORDER.get().map(Box::as_ref)
+}
+
+/// A snapshot of the statistics of a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionStats {
+    /// the number of queries run.
+    pub queries: u64,
+    /// the number of queries waiting for their response.
+    pub in_flight: u64,
+    /// the number of queries which failed.
+    pub errors: u64,
+    /// the number of documents received.
+    pub rows: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    queries: AtomicU64,
+    in_flight: AtomicU64,
+    errors: AtomicU64,
+    rows: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn snapshot(&self) -> SessionStats {
+        SessionStats {
+            queries: self.queries.load(Ordering::Relaxed),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            rows: self.rows.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn query_started(&self) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "metrics")]
+        if let Some(recorder) = recorder() {
+            recorder.increment_gauge(QUERIES_IN_FLIGHT, 1.);
+        }
+    }
+
+    pub(crate) fn query_finished(&self, latency: Duration, rows: u64, error: Option<&ReqlError>) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        self.rows.fetch_add(rows, Ordering::Relaxed);
+        if error.is_some() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(recorder) = recorder() {
+            recorder.increment_gauge(QUERIES_IN_FLIGHT, -1.);
+            recorder.record_histogram(QUERY_DURATION, latency.as_secs_f64());
+            if let Some(error) = error {
+                recorder.increment_counter(QUERY_ERRORS, &[("kind", error.kind())], 1);
+            }
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = latency;
+    }
+
+    pub(crate) fn bytes_sent(&self, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+
+        #[cfg(feature = "metrics")]
+        if let Some(recorder) = recorder() {
+            recorder.increment_counter(BYTES_SENT, &[], bytes as u64);
+        }
+    }
+
+    pub(crate) fn bytes_received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+
+        #[cfg(feature = "metrics")]
+        if let Some(recorder) = recorder() {
+            recorder.increment_counter(BYTES_RECEIVED, &[], bytes as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Counters;
+    use crate::err::ReqlDriverError;
+
+    #[test]
+    fn test_counters_snapshot() {
+        let counters = Counters::default();
+        counters.query_started();
+        counters.query_started();
+        counters.bytes_sent(20);
+        counters.bytes_received(12);
+        counters.query_finished(Duration::from_millis(3), 2, None);
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.queries, 2);
+        assert_eq!(stats.in_flight, 1);
+        assert_eq!(stats.rows, 2);
+        assert_eq!((stats.bytes_sent, stats.bytes_received), (20, 12));
+
+        let error = ReqlDriverError::ConnectionBroken.into();
+        counters.query_finished(Duration::from_millis(1), 0, Some(&error));
+        assert_eq!(counters.snapshot().errors, 1);
+        assert_eq!(counters.snapshot().in_flight, 0);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_recorder() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        use super::{set_recorder, Recorder, QUERY_ERRORS};
+
+        struct Errors(Arc<AtomicU64>);
+
+        impl Recorder for Errors {
+            fn record_histogram(&self, _name: &'static str, _value: f64) {}
+
+            fn increment_counter(
+                &self,
+                name: &'static str,
+                labels: &[(&'static str, &str)],
+                value: u64,
+            ) {
+                if name == QUERY_ERRORS && labels == [("kind", "connection_broken")] {
+                    self.0.fetch_add(value, Ordering::SeqCst);
+                }
+            }
+
+            fn increment_gauge(&self, _name: &'static str, _value: f64) {}
+        }
+
+        let errors = Arc::new(AtomicU64::new(0));
+        set_recorder(Errors(errors.clone())).unwrap();
+        assert!(set_recorder(Errors(errors.clone())).is_err());
+
+        let counters = Counters::default();
+        counters.query_started();
+        counters.query_finished(
+            Duration::from_millis(1),
+            0,
+            Some(&ReqlDriverError::ConnectionBroken.into()),
+        );
+
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+    }
+}
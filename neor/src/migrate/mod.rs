@@ -0,0 +1,588 @@
+//! Versioned schema migrations.
+//!
+//! A [Migrator] applies [Migration]s in the order of their version
+//! and records them in a `_migrations` table, so that every migration
+//! runs once per database. A lock stored in the same table makes sure
+//! that only one instance migrates at a time, and is renewed while
+//! the migrations run.
+//!
+//! When the expected schema can simply be declared, [ensure_schema]
+//! compares it to the server and applies the differences instead.
+//!
+//! ```
+//! use futures::future::BoxFuture;
+//! use neor::arguments::TableCreateOption;
+//! use neor::migrate::{self, Migration, Migrator};
+//! use neor::{r, Result, Session};
+//!
+//! struct CreateUsers;
+//!
+//! impl Migration for CreateUsers {
+//!     fn version(&self) -> u64 {
+//!         1
+//!     }
+//!
+//!     fn name(&self) -> &str {
+//!         "create users"
+//!     }
+//!
+//!     fn up<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+//!         Box::pin(async move {
+//!             migrate::ensure_table(session, "app", "users", TableCreateOption::default()).await?;
+//!             Ok(())
+//!         })
+//!     }
+//!
+//!     fn down<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+//!         Box::pin(async move {
+//!             r.db("app").table_drop("users").run(session).await?;
+//!             Ok(())
+//!         })
+//!     }
+//! }
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     let applied = Migrator::new("app")
+//!         .migration(CreateUsers)
+//!         .up(&conn)
+//!         .await?;
+//!
+//!     assert!(applied.len() <= 1);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::arguments::{IndexCreateOption, TableCreateOption};
+use crate::err::ReqlDriverError;
+use crate::types::{MutationResponse, Time};
+use crate::{args, r, Command, Converter, Func, Result, Session};
+
+pub use schema::*;
+
+mod schema;
+
+/// The name of the table recording the applied migrations
+pub const DEFAULT_MIGRATIONS_TABLE: &str = "_migrations";
+
+const LOCK_ID: &str = "lock";
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// A change to the schema or the data of a database
+///
+/// Migrations are identified by their `version`, which must be unique
+/// and gives the order in which they are applied.
+pub trait Migration: Send + Sync {
+    fn version(&self) -> u64;
+
+    fn name(&self) -> &str;
+
+    fn up<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>>;
+
+    fn down<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Whether a migration has been applied
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// recorded in the migrations table but not registered
+    /// in the [Migrator], e.g. when running an older build.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct MigrationStatus {
+    pub version: u64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<Time>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppliedMigration {
+    id: u64,
+    name: String,
+    applied_at: Time,
+}
+
+/// Applies and rolls back [Migration]s
+pub struct Migrator {
+    db: Cow<'static, str>,
+    table: Cow<'static, str>,
+    lock_ttl: Duration,
+    lock_wait: Duration,
+    migrations: Vec<Box<dyn Migration>>,
+}
+
+impl Migrator {
+    /// Migrations recorded in the `_migrations` table of `db`.
+    pub fn new(db: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            db: db.into(),
+            table: Cow::Borrowed(DEFAULT_MIGRATIONS_TABLE),
+            lock_ttl: Duration::from_secs(600),
+            lock_wait: Duration::from_secs(60),
+            migrations: Vec::new(),
+        }
+    }
+
+    pub fn table(mut self, table: impl Into<Cow<'static, str>>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// How long the lock is held before other instances may take it
+    /// over, in case the instance holding it died (default 10 minutes).
+    ///
+    /// The lock is renewed every third of `lock_ttl` while migrating,
+    /// so a migration may run longer than `lock_ttl`. The migrations
+    /// are aborted with an error when the lock is taken over, or when
+    /// it could not be renewed during `lock_ttl`.
+    pub fn lock_ttl(mut self, lock_ttl: Duration) -> Self {
+        self.lock_ttl = lock_ttl;
+        self
+    }
+
+    /// How long to wait for another instance to release the lock
+    /// before giving up (default 1 minute).
+    pub fn lock_wait(mut self, lock_wait: Duration) -> Self {
+        self.lock_wait = lock_wait;
+        self
+    }
+
+    pub fn migration(mut self, migration: impl Migration + 'static) -> Self {
+        self.migrations.push(Box::new(migration));
+        self
+    }
+
+    /// Report the state of every known and applied migration,
+    /// ordered by version.
+    ///
+    /// Every migration is pending while the migrations table
+    /// does not exist, which is only created when migrating.
+    pub async fn status(&self, session: &Session) -> Result<Vec<MigrationStatus>> {
+        self.check_versions()?;
+
+        let mut applied = match self.prepared(session).await? {
+            true => self.applied(session).await?,
+            false => BTreeMap::new(),
+        };
+        let mut status = Vec::with_capacity(self.migrations.len());
+
+        for migration in self.sorted() {
+            let applied_at = applied
+                .remove(&migration.version())
+                .map(|applied| applied.applied_at);
+            status.push(MigrationStatus {
+                version: migration.version(),
+                name: migration.name().to_owned(),
+                state: match applied_at {
+                    Some(_) => MigrationState::Applied,
+                    None => MigrationState::Pending,
+                },
+                applied_at,
+            });
+        }
+        status.extend(applied.into_values().map(|applied| MigrationStatus {
+            version: applied.id,
+            name: applied.name,
+            state: MigrationState::Unknown,
+            applied_at: Some(applied.applied_at),
+        }));
+        status.sort_by_key(|status| status.version);
+
+        Ok(status)
+    }
+
+    /// Apply every pending migration, returning their versions.
+    pub async fn up(&self, session: &Session) -> Result<Vec<u64>> {
+        self.up_to(session, u64::MAX).await
+    }
+
+    /// Apply the pending migrations up to `version` included.
+    pub async fn up_to(&self, session: &Session, version: u64) -> Result<Vec<u64>> {
+        self.locked(session, self.apply_up(session, version)).await
+    }
+
+    /// Roll back the last applied migration, if any.
+    pub async fn down(&self, session: &Session) -> Result<Option<u64>> {
+        self.locked(session, async {
+            let applied = self.applied(session).await?;
+            match applied.keys().next_back() {
+                Some(&last) => Ok(self.apply_down(session, last.checked_sub(1)).await?.pop()),
+                None => Ok(None),
+            }
+        })
+        .await
+    }
+
+    /// Roll back the applied migrations newer than `version`,
+    /// returning their versions.
+    pub async fn down_to(&self, session: &Session, version: u64) -> Result<Vec<u64>> {
+        self.locked(session, self.apply_down(session, Some(version)))
+            .await
+    }
+
+    /// Release the lock whoever holds it, after an instance died while migrating.
+    pub async fn unlock(&self, session: &Session) -> Result<()> {
+        self.migrations_table()
+            .get(LOCK_ID)
+            .delete(())
+            .run(session)
+            .await?;
+        Ok(())
+    }
+
+    async fn apply_up(&self, session: &Session, version: u64) -> Result<Vec<u64>> {
+        let applied = self.applied(session).await?;
+        let mut done = Vec::new();
+
+        for migration in self.sorted() {
+            if migration.version() > version || applied.contains_key(&migration.version()) {
+                continue;
+            }
+
+            info!(
+                version = migration.version(),
+                name = migration.name(),
+                "applying migration"
+            );
+            migration.up(session).await?;
+
+            let record = AppliedMigration {
+                id: migration.version(),
+                name: migration.name().to_owned(),
+                applied_at: Time::from(OffsetDateTime::now_utc()),
+            };
+            self.migrations_table().insert(&record).run(session).await?;
+            done.push(record.id);
+        }
+
+        Ok(done)
+    }
+
+    async fn apply_down(&self, session: &Session, version: Option<u64>) -> Result<Vec<u64>> {
+        let applied = self.applied(session).await?;
+        let mut done = Vec::new();
+
+        if let Some(unknown) = applied.keys().find(|applied| {
+            Some(**applied) > version && !self.migrations.iter().any(|m| m.version() == **applied)
+        }) {
+            return Err(ReqlDriverError::Other(format!(
+                "cannot roll back migration {}, it is not registered",
+                unknown
+            ))
+            .into());
+        }
+
+        for migration in self.sorted().into_iter().rev() {
+            if Some(migration.version()) <= version || !applied.contains_key(&migration.version()) {
+                continue;
+            }
+
+            info!(
+                version = migration.version(),
+                name = migration.name(),
+                "rolling back migration"
+            );
+            migration.down(session).await?;
+            self.migrations_table()
+                .get(migration.version())
+                .delete(())
+                .run(session)
+                .await?;
+            done.push(migration.version());
+        }
+
+        Ok(done)
+    }
+
+    async fn locked<T>(
+        &self,
+        session: &Session,
+        task: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        self.check_versions()?;
+        self.prepare(session).await?;
+
+        let owner = self.lock(session).await?;
+        // the migrations stop as soon as the lock is lost,
+        // since another instance may be migrating
+        let result = tokio::select! {
+            result = task => result,
+            lost = self.renew(session, &owner) => lost.map(|never| match never {}),
+        };
+
+        // the lock expires anyway, do not hide the result of the migrations
+        if let Err(error) = self.release(session, &owner).await {
+            warn!(db = %self.db, %error, "failed to release the migrations lock");
+        }
+
+        result
+    }
+
+    async fn lock(&self, session: &Session) -> Result<String> {
+        let owner = Uuid::new_v4().to_string();
+        let ttl = self.lock_ttl.as_secs_f64();
+        let started = Instant::now();
+
+        loop {
+            let lock = json!({ "id": LOCK_ID, "owner": owner });
+            // Taking the lock is a single atomic `replace`, which keeps
+            // the current lock unless it is missing or expired.
+            let response: MutationResponse = self
+                .migrations_table()
+                .get(LOCK_ID)
+                .replace(Func::from_fn1(move |current| {
+                    r.branch(
+                        current
+                            .eq(Value::Null)
+                            .or(current.g("expires_at").lt(r.now().cmd())),
+                        args!(
+                            r.expr(lock)
+                                .merge(r.object([r.expr("expires_at"), r.now().cmd() + ttl])),
+                            current
+                        ),
+                    )
+                }))
+                .run(session)
+                .await?
+                .unwrap_or_default()
+                .parse()?;
+
+            if response.inserted + response.replaced == 1 {
+                return Ok(owner);
+            }
+            if started.elapsed() >= self.lock_wait {
+                return Err(ReqlDriverError::Other(format!(
+                    "migrations of `{}` are locked by another instance",
+                    self.db
+                ))
+                .into());
+            }
+
+            tokio::time::sleep(LOCK_RETRY_DELAY).await;
+        }
+    }
+
+    /// Extend the lock held by `owner` until the migrations end,
+    /// failing once it was taken over or could not be renewed
+    /// during `lock_ttl`.
+    async fn renew(&self, session: &Session, owner: &str) -> Result<Infallible> {
+        let ttl = self.lock_ttl.as_secs_f64();
+        let mut renewed = Instant::now();
+
+        loop {
+            tokio::time::sleep((self.lock_ttl / 3).max(LOCK_RETRY_DELAY)).await;
+
+            let owner = owner.to_owned();
+            let response = self
+                .migrations_table()
+                .get(LOCK_ID)
+                .update(Func::from_fn1(move |current| {
+                    r.branch(
+                        current.g("owner").eq(owner.as_str()),
+                        args!(
+                            r.object([r.expr("expires_at"), r.now().cmd() + ttl]),
+                            r.expr(json!({}))
+                        ),
+                    )
+                }))
+                .run(session)
+                .await
+                .and_then(|response| response.unwrap_or_default().parse::<MutationResponse>());
+
+            match response {
+                Ok(response) if response.replaced == 0 => {
+                    return Err(ReqlDriverError::Other(format!(
+                        "the migrations lock of `{}` was taken over by another instance",
+                        self.db
+                    ))
+                    .into());
+                }
+                Ok(_) => renewed = Instant::now(),
+                Err(error) if renewed.elapsed() >= self.lock_ttl => {
+                    return Err(ReqlDriverError::Other(format!(
+                        "the migrations lock of `{}` could not be renewed: {}",
+                        self.db, error
+                    ))
+                    .into());
+                }
+                Err(error) => warn!(db = %self.db, %error, "failed to renew the migrations lock"),
+            }
+        }
+    }
+
+    async fn release(&self, session: &Session, owner: &str) -> Result<()> {
+        self.migrations_table()
+            .filter(json!({ "id": LOCK_ID, "owner": owner }))
+            .delete(())
+            .run(session)
+            .await?;
+        Ok(())
+    }
+
+    async fn prepare(&self, session: &Session) -> Result<()> {
+        ensure_db(session, &self.db).await?;
+        ensure_table(session, &self.db, &self.table, TableCreateOption::default()).await?;
+        Ok(())
+    }
+
+    /// Whether the migrations table exists.
+    async fn prepared(&self, session: &Session) -> Result<bool> {
+        Ok(list_contains(session, r.db_list(), &self.db).await?
+            && list_contains(session, r.db(self.db.as_ref()).table_list(), &self.table).await?)
+    }
+
+    async fn applied(&self, session: &Session) -> Result<BTreeMap<u64, AppliedMigration>> {
+        let mut applied = BTreeMap::new();
+        let mut query = self.migrations_table().build_query(session);
+
+        while let Some(batch) = query.try_next().await? {
+            let rows = match batch {
+                Value::Array(rows) => rows,
+                row => vec![row],
+            };
+            for row in rows {
+                // the lock shares the table but has a string `id`
+                if row["id"].is_u64() {
+                    let migration: AppliedMigration = serde_json::from_value(row)?;
+                    applied.insert(migration.id, migration);
+                }
+            }
+        }
+
+        Ok(applied)
+    }
+
+    fn check_versions(&self) -> Result<()> {
+        let mut versions: Vec<u64> = self.migrations.iter().map(|m| m.version()).collect();
+        versions.sort_unstable();
+
+        match versions.windows(2).find(|pair| pair[0] == pair[1]) {
+            Some(pair) => Err(ReqlDriverError::Other(format!(
+                "several migrations have the version {}",
+                pair[0]
+            ))
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    fn sorted(&self) -> Vec<&dyn Migration> {
+        let mut migrations: Vec<&dyn Migration> =
+            self.migrations.iter().map(AsRef::as_ref).collect();
+        migrations.sort_by_key(|migration| migration.version());
+        migrations
+    }
+
+    fn migrations_table(&self) -> Command {
+        r.db(self.db.as_ref()).table(self.table.as_ref())
+    }
+}
+
+/// Create a database unless it already exists.
+///
+/// Returns whether the database was created.
+pub async fn ensure_db(session: &Session, db: &str) -> Result<bool> {
+    if list_contains(session, r.db_list(), db).await? {
+        return Ok(false);
+    }
+
+    match r.db_create(db).run(session).await {
+        Ok(_) => Ok(true),
+        // another instance may have created it in the meantime
+        Err(error) => match list_contains(session, r.db_list(), db).await? {
+            true => Ok(false),
+            false => Err(error),
+        },
+    }
+}
+
+/// Create a table unless it already exists, and wait for it to be ready.
+///
+/// Returns whether the table was created.
+pub async fn ensure_table(
+    session: &Session,
+    db: &str,
+    table: &str,
+    options: TableCreateOption,
+) -> Result<bool> {
+    let database = r.db(db);
+
+    if list_contains(session, database.table_list(), table).await? {
+        return Ok(false);
+    }
+
+    let created = match database
+        .table_create(args!(table, options))
+        .run(session)
+        .await
+    {
+        Ok(_) => true,
+        Err(error) => match list_contains(session, database.table_list(), table).await? {
+            true => false,
+            false => return Err(error),
+        },
+    };
+    database.table(table).wait(()).run(session).await?;
+
+    Ok(created)
+}
+
+/// Create a secondary index on `table` unless it already exists,
+/// and wait for it to be ready.
+///
+/// Returns whether the index was created.
+pub async fn ensure_index(
+    session: &Session,
+    table: &Command,
+    index: &str,
+    func: Option<Func>,
+    options: IndexCreateOption,
+) -> Result<bool> {
+    if list_contains(session, table.index_list(), index).await? {
+        return Ok(false);
+    }
+
+    let query = match func {
+        Some(func) => table.index_create(args!(index, func, options)),
+        None => table.index_create(args!(index, options)),
+    };
+    let created = match query.run(session).await {
+        Ok(_) => true,
+        Err(error) => match list_contains(session, table.index_list(), index).await? {
+            true => false,
+            false => return Err(error),
+        },
+    };
+    table.index_wait(index).run(session).await?;
+
+    Ok(created)
+}
+
+pub(crate) async fn list_contains(session: &Session, list: Command, name: &str) -> Result<bool> {
+    let names: Vec<String> = match list.run(session).await? {
+        Some(names) => names.parse()?,
+        None => Vec::new(),
+    };
+
+    Ok(names.iter().any(|item| item == name))
+}
@@ -0,0 +1,732 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::admin::TableConfig;
+use crate::arguments::{
+    Durability, IndexCreateOption, ReconfigureOption, Replicas, TableCreateOption,
+};
+use crate::err::ReqlDriverError;
+use crate::types::IndexStatusResponse;
+use crate::{args, r, Command, Converter, Func, Result, Session};
+
+use super::{ensure_db, ensure_table, list_contains};
+
+/// Declarative description of databases, tables, indexes and write hooks
+///
+/// ```
+/// use neor::arguments::Durability;
+/// use neor::migrate::{self, DbSpec, IndexSpec, Schema, TableSpec};
+/// use neor::{r, Result};
+///
+/// async fn example() -> Result<()> {
+///     let conn = r.connection().connect().await?;
+///     let schema = Schema::new().db(
+///         DbSpec::new("app").table(
+///             TableSpec::new("users")
+///                 .primary_key("uid")
+///                 .durability(Durability::Soft)
+///                 .index(IndexSpec::simple("email"))
+///                 .index(IndexSpec::compound("full_name", ["last_name", "first_name"]))
+///                 .index(IndexSpec::multi("tags")),
+///         ),
+///     );
+///
+///     // prints the planned changes
+///     let plan = migrate::ensure_schema(&conn, schema.clone().dry_run(true)).await?;
+///
+///     let report = migrate::ensure_schema(&conn, schema).await?;
+///
+///     assert!(report.changes == plan.changes);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub dbs: Vec<DbSpec>,
+    /// only compute and print the changes, without applying them.
+    pub dry_run: bool,
+    /// drop the secondary indexes which are not declared.
+    pub prune_indexes: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DbSpec {
+    pub name: Cow<'static, str>,
+    pub tables: Vec<TableSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableSpec {
+    pub name: Cow<'static, str>,
+    pub options: TableCreateOption,
+    pub indexes: Vec<IndexSpec>,
+    pub write_hook: Option<Func>,
+}
+
+/// A secondary index, built on the options of `index_create`
+///
+/// Indexes are compared by name and by their `multi` and `geo` options.
+/// The server does not expose the definition of an index in a form which
+/// can be compared, so the fields or the function of an existing index
+/// are reported as [unverified](Unverified): give a new definition
+/// another name and enable [prune_indexes](Schema::prune_indexes) instead.
+#[derive(Debug, Clone)]
+pub struct IndexSpec {
+    pub name: Cow<'static, str>,
+    pub func: Option<Func>,
+    pub options: IndexCreateOption,
+}
+
+/// A difference between the declared schema and the server
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SchemaChange {
+    CreateDb {
+        db: String,
+    },
+    CreateTable {
+        db: String,
+        table: String,
+    },
+    /// the number of shards or replicas differs.
+    Reconfigure {
+        db: String,
+        table: String,
+    },
+    SetDurability {
+        db: String,
+        table: String,
+        durability: Durability,
+    },
+    CreateIndex {
+        db: String,
+        table: String,
+        index: String,
+    },
+    /// the `multi` or `geo` option of the index differs.
+    RecreateIndex {
+        db: String,
+        table: String,
+        index: String,
+    },
+    DropIndex {
+        db: String,
+        table: String,
+        index: String,
+    },
+    SetWriteHook {
+        db: String,
+        table: String,
+    },
+}
+
+/// A declaration which exists on the server, but whose definition
+/// cannot be compared to it
+///
+/// The server only returns index functions and write hooks in a
+/// compiled form, so a changed function is not detected: give the
+/// index another name, or set the write hook again.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Unverified {
+    IndexFunction {
+        db: String,
+        table: String,
+        index: String,
+    },
+    WriteHook {
+        db: String,
+        table: String,
+    },
+}
+
+/// Changes planned or applied by [ensure_schema]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SchemaReport {
+    pub changes: Vec<SchemaChange>,
+    /// the index functions and write hooks which exist
+    /// but could not be compared, and may differ.
+    pub unverified: Vec<Unverified>,
+    /// whether the changes were only planned.
+    pub dry_run: bool,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn db(mut self, db: DbSpec) -> Self {
+        self.dbs.push(db);
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn prune_indexes(mut self, prune_indexes: bool) -> Self {
+        self.prune_indexes = prune_indexes;
+        self
+    }
+
+    fn table(&self, db: &str, table: &str) -> Result<&TableSpec> {
+        self.dbs
+            .iter()
+            .filter(|spec| spec.name == db)
+            .flat_map(|spec| &spec.tables)
+            .find(|spec| spec.name == table)
+            .ok_or_else(|| {
+                ReqlDriverError::Other(format!(
+                    "table `{}.{}` is not declared in the schema",
+                    db, table
+                ))
+                .into()
+            })
+    }
+}
+
+impl DbSpec {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn table(mut self, table: TableSpec) -> Self {
+        self.tables.push(table);
+        self
+    }
+}
+
+impl TableSpec {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            options: TableCreateOption::default(),
+            indexes: Vec::new(),
+            write_hook: None,
+        }
+    }
+
+    pub fn options(mut self, options: TableCreateOption) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn primary_key(mut self, primary_key: impl Into<Cow<'static, str>>) -> Self {
+        self.options.primary_key = Some(primary_key.into());
+        self
+    }
+
+    pub fn shards(mut self, shards: u8) -> Self {
+        self.options.shards = Some(shards);
+        self
+    }
+
+    pub fn replicas(mut self, replicas: Replicas) -> Self {
+        self.options.replicas = Some(replicas);
+        self
+    }
+
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.options.durability = Some(durability);
+        self
+    }
+
+    pub fn index(mut self, index: IndexSpec) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// The write hook is only set when the table has none, an existing
+    /// hook cannot be compared and is reported as [unverified](Unverified).
+    pub fn write_hook(mut self, func: Func) -> Self {
+        self.write_hook = Some(func);
+        self
+    }
+}
+
+impl IndexSpec {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            func: None,
+            options: IndexCreateOption::default(),
+        }
+    }
+
+    /// Index on the field with the same name.
+    pub fn simple(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(name)
+    }
+
+    /// Index on several fields.
+    pub fn compound<S, T>(name: impl Into<Cow<'static, str>>, fields: T) -> Self
+    where
+        S: Into<String>,
+        T: IntoIterator<Item = S>,
+    {
+        let fields: Vec<String> = fields.into_iter().map(Into::into).collect();
+        let func =
+            Func::from_fn1(move |row| r.array(fields.iter().map(|field| row.g(field.as_str()))));
+
+        Self::new(name).func(func)
+    }
+
+    /// Index on every element of the array field with the same name.
+    pub fn multi(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(name).options(IndexCreateOption::default().multi(true))
+    }
+
+    /// Index on the geometry field with the same name.
+    pub fn geo(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(name).options(IndexCreateOption::default().geo(true))
+    }
+
+    /// Index on the values returned by `func`.
+    pub fn function(name: impl Into<Cow<'static, str>>, func: Func) -> Self {
+        Self::new(name).func(func)
+    }
+
+    pub fn func(mut self, func: Func) -> Self {
+        self.func = Some(func);
+        self
+    }
+
+    pub fn options(mut self, options: IndexCreateOption) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn create(&self, table: &Command) -> Command {
+        match &self.func {
+            Some(func) => table.index_create(args!(self.name.as_ref(), func.clone(), self.options)),
+            None => table.index_create(args!(self.name.as_ref(), self.options)),
+        }
+    }
+}
+
+/// Compare the schema to the server, and apply the differences
+/// unless it is a dry run.
+///
+/// The returned report lists the changes, which a dry run
+/// also prints to the standard output.
+///
+/// Missing databases, tables, indexes and write hooks are created,
+/// tables are reconfigured and indexes whose options changed are
+/// recreated, then the indexes are awaited. A table whose primary key
+/// differs is an error, since it could only be fixed by dropping it.
+///
+/// The functions of existing indexes and write hooks cannot be
+/// compared, they are listed as [unverified](SchemaReport::unverified).
+pub async fn ensure_schema(session: &Session, schema: Schema) -> Result<SchemaReport> {
+    let mut report = plan(session, &schema).await?;
+    report.dry_run = schema.dry_run;
+
+    if !schema.dry_run {
+        apply(session, &schema, &report.changes).await?;
+    }
+    if report.dry_run {
+        print!("{}", report);
+    }
+
+    Ok(report)
+}
+
+async fn plan(session: &Session, schema: &Schema) -> Result<SchemaReport> {
+    let dbs: Vec<String> = parse_list(r.db_list().run(session).await?)?;
+    let mut report = SchemaReport::default();
+
+    for db_spec in &schema.dbs {
+        let db = db_spec.name.to_string();
+        let tables: Vec<String> = match dbs.contains(&db) {
+            true => parse_list(r.db(db.as_str()).table_list().run(session).await?)?,
+            false => {
+                report
+                    .changes
+                    .push(SchemaChange::CreateDb { db: db.clone() });
+                Vec::new()
+            }
+        };
+
+        for table_spec in &db_spec.tables {
+            let table = table_spec.name.to_string();
+
+            if tables.contains(&table) {
+                let query = r.db(db.as_str()).table(table.as_str());
+                plan_table(session, schema, &query, &db, table_spec, &mut report).await?;
+                continue;
+            }
+
+            report.changes.push(SchemaChange::CreateTable {
+                db: db.clone(),
+                table: table.clone(),
+            });
+            report
+                .changes
+                .extend(
+                    table_spec
+                        .indexes
+                        .iter()
+                        .map(|index| SchemaChange::CreateIndex {
+                            db: db.clone(),
+                            table: table.clone(),
+                            index: index.name.to_string(),
+                        }),
+                );
+            if table_spec.write_hook.is_some() {
+                report.changes.push(SchemaChange::SetWriteHook {
+                    db: db.clone(),
+                    table,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+async fn plan_table(
+    session: &Session,
+    schema: &Schema,
+    query: &Command,
+    db: &str,
+    spec: &TableSpec,
+    report: &mut SchemaReport,
+) -> Result<()> {
+    let changes = &mut report.changes;
+    let db = db.to_owned();
+    let table = spec.name.to_string();
+    let config: TableConfig = query
+        .config()
+        .run(session)
+        .await?
+        .unwrap_or_default()
+        .parse()?;
+
+    if let Some(primary_key) = &spec.options.primary_key {
+        if *primary_key != config.primary_key {
+            return Err(ReqlDriverError::Other(format!(
+                "table `{}.{}` has the primary key `{}` instead of `{}`",
+                db, table, config.primary_key, primary_key
+            ))
+            .into());
+        }
+    }
+
+    let shards = config.shards.len();
+    let replicas = config
+        .shards
+        .first()
+        .map_or(0, |shard| shard.replicas.len());
+    let reshard = matches!(spec.options.shards, Some(wanted) if usize::from(wanted) != shards);
+    let replicate =
+        matches!(spec.options.replicas, Some(Replicas::Int(wanted)) if wanted != replicas);
+    if reshard || replicate {
+        changes.push(SchemaChange::Reconfigure {
+            db: db.clone(),
+            table: table.clone(),
+        });
+    }
+
+    if let Some(durability) = spec.options.durability {
+        if durability != config.durability {
+            changes.push(SchemaChange::SetDurability {
+                db: db.clone(),
+                table: table.clone(),
+                durability,
+            });
+        }
+    }
+
+    let indexes: Vec<IndexStatusResponse> = query
+        .index_status(())
+        .run(session)
+        .await?
+        .unwrap_or_default()
+        .parse()?;
+    let indexes: HashMap<&str, &IndexStatusResponse> = indexes
+        .iter()
+        .map(|index| (index.index.as_ref(), index))
+        .collect();
+
+    for index in &spec.indexes {
+        let change = match indexes.get(index.name.as_ref()) {
+            None => SchemaChange::CreateIndex {
+                db: db.clone(),
+                table: table.clone(),
+                index: index.name.to_string(),
+            },
+            Some(current)
+                if current.multi != index.options.multi.unwrap_or(false)
+                    || current.geo != index.options.geo.unwrap_or(false) =>
+            {
+                SchemaChange::RecreateIndex {
+                    db: db.clone(),
+                    table: table.clone(),
+                    index: index.name.to_string(),
+                }
+            }
+            Some(_) if index.func.is_some() => {
+                report.unverified.push(Unverified::IndexFunction {
+                    db: db.clone(),
+                    table: table.clone(),
+                    index: index.name.to_string(),
+                });
+                continue;
+            }
+            Some(_) => continue,
+        };
+        changes.push(change);
+    }
+
+    if schema.prune_indexes {
+        let mut unknown: Vec<&str> = indexes
+            .keys()
+            .filter(|name| !spec.indexes.iter().any(|index| index.name == **name))
+            .copied()
+            .collect();
+        unknown.sort_unstable();
+        changes.extend(unknown.into_iter().map(|index| SchemaChange::DropIndex {
+            db: db.clone(),
+            table: table.clone(),
+            index: index.to_owned(),
+        }));
+    }
+
+    if spec.write_hook.is_some() {
+        let write_hook = query.get_write_hook().run(session).await?;
+        match write_hook.unwrap_or_default().is_null() {
+            true => changes.push(SchemaChange::SetWriteHook { db, table }),
+            false => report.unverified.push(Unverified::WriteHook { db, table }),
+        }
+    }
+
+    Ok(())
+}
+
+async fn apply(session: &Session, schema: &Schema, changes: &[SchemaChange]) -> Result<()> {
+    let mut indexed = Vec::new();
+
+    for change in changes {
+        info!(%change, "applying schema change");
+
+        match change {
+            SchemaChange::CreateDb { db } => {
+                ensure_db(session, db).await?;
+            }
+            SchemaChange::CreateTable { db, table } => {
+                let spec = schema.table(db, table)?;
+                ensure_table(session, db, table, spec.options.clone()).await?;
+            }
+            SchemaChange::Reconfigure { db, table } => {
+                let spec = schema.table(db, table)?;
+                let query = r.db(db.as_str()).table(table.as_str());
+                let config: TableConfig = query
+                    .config()
+                    .run(session)
+                    .await?
+                    .unwrap_or_default()
+                    .parse()?;
+                let mut options = ReconfigureOption::default();
+                // both are required, keep the current value of the one not declared
+                options.shards = Some(spec.options.shards.unwrap_or(config.shards.len() as u8));
+                options.replicas = Some(spec.options.replicas.clone().unwrap_or_else(|| {
+                    Replicas::Int(
+                        config
+                            .shards
+                            .first()
+                            .map_or(1, |shard| shard.replicas.len()),
+                    )
+                }));
+                query.reconfigure(options).run(session).await?;
+                query.wait(()).run(session).await?;
+            }
+            SchemaChange::SetDurability {
+                db,
+                table,
+                durability,
+            } => {
+                r.db(db.as_str())
+                    .table(table.as_str())
+                    .config()
+                    .update(json!({ "durability": durability }))
+                    .run(session)
+                    .await?;
+            }
+            SchemaChange::CreateIndex { db, table, index } => {
+                let query = r.db(db.as_str()).table(table.as_str());
+                let spec = index_spec(schema.table(db, table)?, index)?;
+                if !list_contains(session, query.index_list(), index).await? {
+                    spec.create(&query).run(session).await?;
+                }
+                indexed.push(query);
+            }
+            SchemaChange::RecreateIndex { db, table, index } => {
+                let query = r.db(db.as_str()).table(table.as_str());
+                let spec = index_spec(schema.table(db, table)?, index)?;
+                query.index_drop(index.as_str()).run(session).await?;
+                spec.create(&query).run(session).await?;
+                indexed.push(query);
+            }
+            SchemaChange::DropIndex { db, table, index } => {
+                r.db(db.as_str())
+                    .table(table.as_str())
+                    .index_drop(index.as_str())
+                    .run(session)
+                    .await?;
+            }
+            SchemaChange::SetWriteHook { db, table } => {
+                if let Some(func) = &schema.table(db, table)?.write_hook {
+                    r.db(db.as_str())
+                        .table(table.as_str())
+                        .set_write_hook(func.clone())
+                        .run(session)
+                        .await?;
+                }
+            }
+        }
+    }
+
+    for query in indexed {
+        query.index_wait(()).run(session).await?;
+    }
+
+    Ok(())
+}
+
+fn index_spec<'a>(table: &'a TableSpec, index: &str) -> Result<&'a IndexSpec> {
+    table
+        .indexes
+        .iter()
+        .find(|spec| spec.name == index)
+        .ok_or_else(|| {
+            ReqlDriverError::Other(format!(
+                "index `{}` of table `{}` is not declared in the schema",
+                index, table.name
+            ))
+            .into()
+        })
+}
+
+fn parse_list(list: Option<Value>) -> Result<Vec<String>> {
+    match list {
+        Some(list) => list.parse(),
+        None => Ok(Vec::new()),
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CreateDb { db } => write!(f, "create database `{}`", db),
+            Self::CreateTable { db, table } => write!(f, "create table `{}.{}`", db, table),
+            Self::Reconfigure { db, table } => write!(f, "reconfigure table `{}.{}`", db, table),
+            Self::SetDurability {
+                db,
+                table,
+                durability,
+            } => write!(
+                f,
+                "set durability of `{}.{}` to {:?}",
+                db, table, durability
+            ),
+            Self::CreateIndex { db, table, index } => {
+                write!(f, "create index `{}` on `{}.{}`", index, db, table)
+            }
+            Self::RecreateIndex { db, table, index } => {
+                write!(f, "recreate index `{}` on `{}.{}`", index, db, table)
+            }
+            Self::DropIndex { db, table, index } => {
+                write!(f, "drop index `{}` on `{}.{}`", index, db, table)
+            }
+            Self::SetWriteHook { db, table } => {
+                write!(f, "set write hook on `{}.{}`", db, table)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Unverified {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IndexFunction { db, table, index } => {
+                write!(f, "function of index `{}` on `{}.{}`", index, db, table)
+            }
+            Self::WriteHook { db, table } => write!(f, "write hook on `{}.{}`", db, table),
+        }
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() && self.unverified.is_empty() {
+            return f.write_str("schema is up to date");
+        }
+
+        if !self.changes.is_empty() {
+            let verb = if self.dry_run { "planned" } else { "applied" };
+            writeln!(f, "{} {} change(s):", verb, self.changes.len())?;
+            for change in &self.changes {
+                writeln!(f, "  - {}", change)?;
+            }
+        }
+        if !self.unverified.is_empty() {
+            writeln!(
+                f,
+                "could not verify {} definition(s):",
+                self.unverified.len()
+            )?;
+            for unverified in &self.unverified {
+                writeln!(f, "  - {}", unverified)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arguments::Durability;
+
+    use super::{SchemaChange, SchemaReport, Unverified};
+
+    #[test]
+    fn test_report_display() {
+        let report = SchemaReport {
+            changes: vec![
+                SchemaChange::CreateTable {
+                    db: "app".to_owned(),
+                    table: "users".to_owned(),
+                },
+                SchemaChange::SetDurability {
+                    db: "app".to_owned(),
+                    table: "posts".to_owned(),
+                    durability: Durability::Soft,
+                },
+            ],
+            unverified: vec![Unverified::WriteHook {
+                db: "app".to_owned(),
+                table: "posts".to_owned(),
+            }],
+            dry_run: true,
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "planned 2 change(s):\n  - create table `app.users`\n  - set durability of `app.posts` to Soft\n\
+             could not verify 1 definition(s):\n  - write hook on `app.posts`\n"
+        );
+        assert_eq!(SchemaReport::default().to_string(), "schema is up to date");
+    }
+}
@@ -1,14 +1,18 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
-use std::{fmt, str};
+use std::sync::Arc;
+use std::{fmt, io, str};
 
 use ql2::query::QueryType;
 use ql2::term::TermType;
 use serde::ser::{self, Serialize, Serializer};
-use serde_json::value::{Number, Value};
+use serde_json::ser::{CompactFormatter, Formatter};
+use serde_json::value::{Number, RawValue, Value};
 
 use crate::arguments::RunOption;
 use crate::cmd::run::Db;
-use crate::{err, r};
+use crate::constants::{DATA_SIZE, HEADER_SIZE, TOKEN_SIZE};
+use crate::{err, r, template, var_counter};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum Datum {
@@ -18,6 +22,76 @@ pub(crate) enum Datum {
     String(String),
     Array(Vec<Datum>),
     Object(HashMap<String, Datum>),
+    /// A user value already serialized as a term
+    Raw(RawDatum),
+    /// A parameter of a [Template](crate::template::Template)
+    Param(Cow<'static, str>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RawDatum(pub(crate) Box<RawValue>);
+
+impl PartialEq for RawDatum {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.get() == other.0.get()
+    }
+}
+
+impl Eq for RawDatum {}
+
+/// Writes JSON as a term, where arrays are `MAKE_ARRAY` terms.
+struct TermFormatter;
+
+impl Formatter for TermFormatter {
+    fn begin_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        write!(writer, "[{},[", TermType::MakeArray as i32)
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(b"]]")
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        CompactFormatter.begin_array_value(writer, first)
+    }
+}
+
+impl Datum {
+    /// Serialize a user value once, straight to its term. Objects and
+    /// arrays are kept as JSON, which is then written as is in the queries.
+    fn from_serialize<T>(value: T) -> super::Result<Self>
+    where
+        T: Serialize,
+    {
+        let json = to_term_json(value)?;
+
+        match json.as_bytes().first() {
+            Some(b'{' | b'[') => Ok(Self::Raw(RawDatum(RawValue::from_string(json)?))),
+            _ => Ok(serde_json::from_str::<Value>(&json)?.into()),
+        }
+    }
+}
+
+/// Serialize a user value to the JSON of its term.
+pub(crate) fn to_term_json<T>(value: T) -> super::Result<String>
+where
+    T: Serialize,
+{
+    let mut json = Vec::with_capacity(128);
+    let mut serializer = serde_json::Serializer::with_formatter(&mut json, TermFormatter);
+    value.serialize(&mut serializer)?;
+
+    // serde_json only writes valid UTF-8
+    String::from_utf8(json).map_err(|error| err::ReqlDriverError::Other(error.to_string()).into())
 }
 
 impl Default for Datum {
@@ -38,6 +112,8 @@ impl Serialize for Datum {
             Self::String(string) => string.serialize(serializer),
             Self::Array(arr) => (TermType::MakeArray as i32, arr).serialize(serializer),
             Self::Object(map) => map.serialize(serializer),
+            Self::Raw(RawDatum(json)) => json.serialize(serializer),
+            Self::Param(name) => template::serialize_param(name, serializer),
         }
     }
 }
@@ -60,25 +136,54 @@ impl From<Value> for Datum {
 }
 
 /// The query that will be sent to RethinkDB
+///
+/// A query is a tree of terms shared between the queries built from
+/// it, so cloning or chaining a query does not copy its terms.
 #[derive(Debug, Clone)]
 pub struct Command {
+    term: Arc<TermNode>,
+}
+
+#[derive(Debug, Clone)]
+struct TermNode {
     typ: TermType,
     datum: Option<super::Result<Datum>>,
-    #[doc(hidden)]
-    pub args: VecDeque<super::Result<Command>>,
-    opts: Option<super::Result<Datum>>,
+    args: VecDeque<super::Result<Command>>,
+    opts: Option<super::Result<HashMap<String, Command>>>,
     change_feed: bool,
+    /// The whole term already serialized, by a bound template
+    prepared: Option<RawDatum>,
 }
 
 impl Command {
     #[doc(hidden)]
     pub fn new(typ: TermType) -> Self {
         Self {
-            typ,
-            datum: None,
-            args: VecDeque::new(),
-            opts: None,
-            change_feed: false,
+            term: Arc::new(TermNode {
+                typ,
+                datum: None,
+                args: VecDeque::new(),
+                opts: None,
+                change_feed: false,
+                prepared: None,
+            }),
+        }
+    }
+
+    // Only the terms built by the query itself are changed in place,
+    // the shared ones are copied first, without their subterms.
+    fn term_mut(&mut self) -> &mut TermNode {
+        let term = Arc::make_mut(&mut self.term);
+        term.prepared = None;
+        term
+    }
+
+    /// The query, sent as the given JSON rather than serialized again.
+    pub(crate) fn with_prepared(&self, json: Box<RawValue>) -> Self {
+        let mut term = TermNode::clone(&self.term);
+        term.prepared = Some(RawDatum(json));
+        Self {
+            term: Arc::new(term),
         }
     }
 
@@ -89,15 +194,16 @@ impl Command {
     }
 
     pub(crate) fn with_parent(mut self, parent: &Command) -> Self {
-        self.change_feed = self.change_feed || parent.change_feed;
-        self.args.push_front(Ok(parent.to_owned()));
+        let term = self.term_mut();
+        term.change_feed = term.change_feed || parent.term.change_feed;
+        term.args.push_front(Ok(parent.clone()));
         self
     }
 
     #[doc(hidden)]
     pub fn with_arg(mut self, arg: impl Into<Command>) -> Self {
         let arg = arg.into();
-        self.args.push_back(Ok(arg));
+        self.term_mut().args.push_back(Ok(arg));
         self
     }
 
@@ -106,9 +212,29 @@ impl Command {
         T: Serialize,
     {
         let opts = serde_json::to_value(&opts)
-            .map(Into::into)
-            .map_err(Into::into);
-        self.opts = Some(opts);
+            .map_err(Into::into)
+            .and_then(|value| match value {
+                Value::Object(map) => Ok(map
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect()),
+                Value::Null => Ok(HashMap::new()),
+                _ => Err(err::ReqlDriverError::Other(
+                    "options must serialize to an object".to_owned(),
+                )
+                .into()),
+            });
+        self.term_mut().opts = Some(opts);
+        self
+    }
+
+    /// Sets an option whose value is a term, such as a function,
+    /// rather than plain data. It must be called after `with_opts`.
+    pub(crate) fn with_opt(mut self, key: &str, value: impl Into<Command>) -> Self {
+        let opts = self.term_mut().opts.get_or_insert_with(|| Ok(HashMap::new()));
+        if let Ok(opts) = opts {
+            opts.insert(key.to_owned(), value.into());
+        }
         self
     }
 
@@ -117,18 +243,64 @@ impl Command {
     where
         T: Serialize,
     {
-        serde_json::to_value(arg)
-            .map_err(super::ReqlError::from)
-            .into()
+        Datum::from_serialize(arg).into()
     }
 
     pub(crate) fn mark_change_feed(mut self) -> Self {
-        self.change_feed = true;
+        self.term_mut().change_feed = true;
         self
     }
 
     pub(crate) fn change_feed(&self) -> bool {
-        self.change_feed
+        self.term.change_feed
+    }
+
+    /// Whether the query writes documents or changes the schema,
+    /// looking into every argument and option.
+    pub fn is_write(&self) -> bool {
+        let write = matches!(
+            self.term.typ,
+            TermType::Insert
+                | TermType::Update
+                | TermType::Replace
+                | TermType::Delete
+                | TermType::DbCreate
+                | TermType::DbDrop
+                | TermType::TableCreate
+                | TermType::TableDrop
+                | TermType::IndexCreate
+                | TermType::IndexDrop
+                | TermType::IndexRename
+                | TermType::Reconfigure
+                | TermType::Rebalance
+                | TermType::SetWriteHook
+                | TermType::Grant
+        );
+
+        write
+            || self.term.args.iter().flatten().any(Command::is_write)
+            || self
+                .term
+                .opts
+                .iter()
+                .flatten()
+                .any(|opts| opts.values().any(Command::is_write))
+    }
+
+    /// The type of the last term of the query.
+    pub fn term_type(&self) -> TermType {
+        self.term.typ
+    }
+
+    /// The name of the table the query starts from, if any.
+    pub fn root_table(&self) -> Option<&str> {
+        match self.term.typ {
+            TermType::Table => match self.term.args.back()?.as_ref().ok()?.term.datum.as_ref()? {
+                Ok(Datum::String(name)) => Some(name),
+                _ => None,
+            },
+            _ => self.term.args.front()?.as_ref().ok()?.root_table(),
+        }
     }
 
     // pub(crate) fn into_arg(&self) -> Self {
@@ -145,7 +317,7 @@ impl From<Datum> for Command {
 impl From<super::Result<Datum>> for Command {
     fn from(result: super::Result<Datum>) -> Self {
         let mut query = Self::new(TermType::Datum);
-        query.datum = Some(result);
+        query.term_mut().datum = Some(result);
         query
     }
 }
@@ -176,22 +348,51 @@ impl Serialize for Query<'_> {
         S: Serializer,
     {
         let Query(cmd) = self;
-        match cmd.typ {
-            TermType::Datum => match &cmd.datum {
+        Term { cmd, depth: 0 }.serialize(serializer)
+    }
+}
+
+// A term together with the number of functions enclosing it,
+// which tells whether `r.row` can be bound unambiguously.
+struct Term<'a> {
+    cmd: &'a Command,
+    depth: usize,
+}
+
+impl Serialize for Term<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let Term { cmd, depth } = *self;
+        if let Some(RawDatum(json)) = &cmd.term.prepared {
+            return json.serialize(serializer);
+        }
+        match cmd.term.typ {
+            TermType::Datum => match &cmd.term.datum {
                 Some(Ok(datum)) => datum.serialize(serializer),
                 Some(Err(error)) => Err(ser::Error::custom(error)),
                 _ => (None as Option<Datum>).serialize(serializer),
             },
+            TermType::ImplicitVar if depth == 0 => Err(ser::Error::custom(
+                "`r.row` can only be used in the argument of a command taking a function",
+            )),
+            TermType::ImplicitVar if depth > 1 => Err(ser::Error::custom(
+                "`r.row` is ambiguous inside nested functions, use `func!` instead",
+            )),
             _ => {
-                let typ = cmd.typ as i32;
-                match &cmd.opts {
-                    Some(Ok(map)) => (
+                let typ = cmd.term.typ as i32;
+                match &cmd.term.opts {
+                    Some(Ok(opts)) => (
                         typ,
-                        to_query_result(&cmd.args).map_err(ser::Error::custom)?,
-                        map,
+                        to_query_result(cmd, depth).map_err(ser::Error::custom)?,
+                        Opts { opts, depth },
                     )
                         .serialize(serializer),
-                    None => (typ, to_query_result(&cmd.args).map_err(ser::Error::custom)?)
+                    None => (
+                        typ,
+                        to_query_result(cmd, depth).map_err(ser::Error::custom)?,
+                    )
                         .serialize(serializer),
                     Some(Err(error)) => Err(ser::Error::custom(error)),
                 }
@@ -200,15 +401,102 @@ impl Serialize for Query<'_> {
     }
 }
 
-fn to_query_result(args: &VecDeque<super::Result<Command>>) -> super::Result<Vec<Query<'_>>> {
-    let mut vec = Vec::with_capacity(args.len());
-    for result in args {
+struct Opts<'a> {
+    opts: &'a HashMap<String, Command>,
+    depth: usize,
+}
+
+impl Serialize for Opts<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let depth = self.depth;
+        serializer.collect_map(
+            self.opts
+                .iter()
+                .map(|(key, cmd)| (key, Term { cmd, depth })),
+        )
+    }
+}
+
+enum Arg<'a> {
+    Term(Term<'a>),
+    ImplicitFunc(Term<'a>),
+}
+
+impl Serialize for Arg<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Term(term) => term.serialize(serializer),
+            Self::ImplicitFunc(body) => {
+                let ids = (TermType::MakeArray as i32, [var_counter()]);
+                (TermType::Func as i32, (ids, body)).serialize(serializer)
+            }
+        }
+    }
+}
+
+fn to_query_result(cmd: &Command, depth: usize) -> super::Result<Vec<Arg<'_>>> {
+    let start = implicit_func_start(cmd.term.typ);
+    let mut vec = Vec::with_capacity(cmd.term.args.len());
+    for (i, result) in cmd.term.args.iter().enumerate() {
         let arg = result.as_ref().map_err(|error| error.clone())?;
-        vec.push(Query(arg));
+        let arg = match start {
+            Some(start) if i >= start && wraps_implicit_var(arg) => Arg::ImplicitFunc(Term {
+                cmd: arg,
+                depth: depth + 1,
+            }),
+            _ if cmd.term.typ == TermType::Func => Arg::Term(Term {
+                cmd: arg,
+                depth: depth + 1,
+            }),
+            _ => Arg::Term(Term { cmd: arg, depth }),
+        };
+        vec.push(arg);
     }
     Ok(vec)
 }
 
+// Position of the first argument that is wrapped into a one-argument
+// function when it uses `r.row`, mirroring `func_wrap` in the official drivers.
+fn implicit_func_start(typ: TermType) -> Option<usize> {
+    match typ {
+        TermType::Asc | TermType::Desc => Some(0),
+        TermType::Filter
+        | TermType::Map
+        | TermType::ConcatMap
+        | TermType::OrderBy
+        | TermType::Group
+        | TermType::Count
+        | TermType::Contains
+        | TermType::Sum
+        | TermType::Avg
+        | TermType::Min
+        | TermType::Max
+        | TermType::Update
+        | TermType::Replace
+        | TermType::Merge
+        | TermType::ForEach
+        | TermType::Reduce
+        | TermType::EqJoin
+        | TermType::IndexCreate => Some(1),
+        _ => None,
+    }
+}
+
+// `asc` and `desc` wrap their own argument, so `order_by` leaves them as is.
+fn wraps_implicit_var(cmd: &Command) -> bool {
+    !matches!(cmd.term.typ, TermType::Asc | TermType::Desc) && has_implicit_var(cmd)
+}
+
+fn has_implicit_var(cmd: &Command) -> bool {
+    cmd.term.typ == TermType::ImplicitVar || cmd.term.args.iter().flatten().any(has_implicit_var)
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Payload<'a>(
     pub(crate) QueryType,
@@ -234,6 +522,19 @@ impl Payload<'_> {
     pub(crate) fn to_bytes(&self) -> Result<Vec<u8>, err::ReqlError> {
         Ok(serde_json::to_vec(self)?)
     }
+
+    /// Write the query frame, its token, length and query, to `buf`.
+    /// The length is written once the query is serialized after it.
+    pub(crate) fn encode_into(&self, token: u64, buf: &mut Vec<u8>) -> Result<(), err::ReqlError> {
+        buf.clear();
+        buf.extend_from_slice(&token.to_le_bytes());
+        buf.extend_from_slice(&[0; DATA_SIZE]);
+        serde_json::to_writer(&mut *buf, self)?;
+
+        let data_len = (buf.len() - HEADER_SIZE) as u32;
+        buf[TOKEN_SIZE..HEADER_SIZE].copy_from_slice(&data_len.to_le_bytes());
+        Ok(())
+    }
 }
 
 // for debugging purposes only
@@ -260,3 +561,153 @@ impl Serialize for Db {
         Query(&cmd).serialize(serializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use std::sync::Arc;
+
+    use ql2::query::QueryType;
+
+    use super::{Payload, Query};
+    use crate::arguments::{HttpOption, InsertOption, Page};
+    use crate::{args, r, Command, Func};
+
+    fn serialize(cmd: &Command) -> serde_json::Result<Value> {
+        serde_json::to_value(Query(cmd))
+    }
+
+    #[test]
+    fn test_root_table() {
+        let query = r.db("blog").table("posts").get(1).g("title");
+
+        assert_eq!(query.root_table(), Some("posts"));
+        assert_eq!(r.table("users").root_table(), Some("users"));
+        assert_eq!(r.db_list().root_table(), None);
+    }
+
+    #[test]
+    fn test_chaining_shares_parent() {
+        let table = r.table("posts");
+        let first = table.get(1);
+        let second = table.get(2);
+
+        let parent = |query: &Command| query.term.args[0].as_ref().unwrap().term.clone();
+        assert!(Arc::ptr_eq(&parent(&first), &table.term));
+        assert!(Arc::ptr_eq(&parent(&second), &table.term));
+        assert_eq!(serialize(&first).unwrap(), json!([16, [[15, ["posts"], {}], 1]]));
+    }
+
+    #[test]
+    fn test_user_value_is_written_as_term() {
+        let query = Command::from_json(json!({"tags": ["a", []], "n": null}));
+
+        assert_eq!(
+            serde_json::to_string(&Query(&query)).unwrap(),
+            r#"{"n":null,"tags":[2,["a",[2,[]]]]}"#
+        );
+        assert_eq!(serialize(&Command::from_json("posts")).unwrap(), json!("posts"));
+    }
+
+    #[test]
+    fn test_encode_frame() {
+        let query = r.expr([1, 2]);
+        let payload = Payload(QueryType::Start, Some(Query(&query)), Default::default());
+        let mut buf = b"previous query".to_vec();
+        payload.encode_into(7, &mut buf).unwrap();
+
+        assert_eq!(buf[..8], 7u64.to_le_bytes());
+        assert_eq!(buf[8..12], (buf.len() as u32 - 12).to_le_bytes());
+        assert_eq!(&buf[12..], br#"[1,[2,[1,2]],{}]"#);
+    }
+
+    #[test]
+    fn test_row_is_wrapped_into_func() {
+        let query = r.table("users").filter(r.row().g("age").gt(18));
+        let value = serialize(&query).unwrap();
+        let predicate = &value[1][1];
+
+        assert_eq!(predicate[0], json!(69));
+        assert_eq!(predicate[1][1], json!([21, [[31, [[13, []], "age"]], 18]]));
+    }
+
+    #[test]
+    fn test_row_is_wrapped_in_sort_key() {
+        let query = r.table("users").order_by(r.desc(r.row().g("age")));
+        let value = serialize(&query).unwrap();
+        let key = &value[1][1];
+
+        assert_eq!(key[0], json!(74));
+        assert_eq!(key[1][0][0], json!(69));
+    }
+
+    #[test]
+    fn test_query_without_row_is_unchanged() {
+        let query = r.table("users").filter(json!({ "age": 18 }));
+        let value = serialize(&query).unwrap();
+
+        assert_eq!(value, json!([39, [[15, ["users"], {}], { "age": 18 }], {}]));
+    }
+
+    #[test]
+    fn test_func_option_is_serialized_as_term() {
+        let insert_option =
+            InsertOption::default().conflict_func(Func::from_fn3(|_id, old_doc, _new_doc| old_doc));
+        let query = r
+            .table("users")
+            .insert(args!(json!({ "id": 1 }), insert_option));
+        let value = serialize(&query).unwrap();
+        let conflict = &value[2]["conflict"];
+
+        assert_eq!(conflict[0], json!(69));
+        assert_eq!(conflict[1][1][0], json!(10));
+    }
+
+    #[test]
+    fn test_http_option_page() {
+        let link_next = HttpOption::default().page(Page::LinkNext).page_limit(2);
+        let func = HttpOption::default().page(Page::Func(Func::from_fn1(|info| info.g("body"))));
+        let link_next = serialize(&r.http(args!("http://example.com", link_next))).unwrap();
+        let func = serialize(&r.http(args!("http://example.com", func))).unwrap();
+
+        assert_eq!(
+            link_next[2],
+            json!({ "page": "link-next", "page_limit": 2 })
+        );
+        assert_eq!(func[2]["page"][0], json!(69));
+    }
+
+    #[test]
+    fn test_revoke_sets_permissions_to_null() {
+        let global = serialize(&r.revoke("bob")).unwrap();
+        let table = serialize(&r.table("posts").revoke("bob")).unwrap();
+
+        assert_eq!(
+            global[1][1],
+            json!({ "read": null, "write": null, "config": null, "connect": null })
+        );
+        assert_eq!(
+            table[1][2],
+            json!({ "read": null, "write": null, "config": null })
+        );
+    }
+
+    #[test]
+    fn test_row_outside_function() {
+        assert!(serialize(&r.row().g("age")).is_err());
+    }
+
+    #[test]
+    fn test_row_in_nested_function() {
+        let nested = r
+            .table("users")
+            .filter(r.row().g("tags").contains(r.row().g("tag")));
+        let in_func = r.table("users").map(Func::from_fn1(|user| {
+            user.g("posts").filter(r.row().g("draft"))
+        }));
+
+        assert!(serialize(&nested).is_err());
+        assert!(serialize(&in_func).is_err());
+    }
+}
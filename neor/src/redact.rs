@@ -0,0 +1,376 @@
+//! Hide sensitive values from the logs of the driver.
+//!
+//! Queries and responses are traced by the driver at the `trace`
+//! level, and failed queries at the `debug` level. The [Redaction] of
+//! a session, set with
+//! [log_redaction](crate::cmd::connect::ConnectionCommand::log_redaction),
+//! masks the values of some fields, truncates long messages and can
+//! hide every value to log only the shape of the queries. It also
+//! applies to the documents quoted in the messages of the server
+//! errors, and to the queries kept in errors.
+//!
+//! ```
+//! use neor::redact::Redaction;
+//! use neor::{r, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let redaction = Redaction::default()
+//!         .mask("email")
+//!         .max_len(2048)
+//!         .shape_only(true);
+//!     let conn = r.connection().log_redaction(redaction).connect().await?;
+//!
+//!     r.table("users").get("bob").run(&conn).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::err::ReqlError;
+
+/// The text replacing the masked values
+pub const REDACTED: &str = "<redacted>";
+
+/// The text replacing the values hidden in [shape_only](Redaction::shape_only) mode
+pub const HIDDEN: &str = "?";
+
+/// The fields masked by default
+pub const DEFAULT_MASKED_FIELDS: [&str; 3] = ["password", "auth_key", "token"];
+
+/// How queries and responses are written in the logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redaction {
+    fields: Vec<Cow<'static, str>>,
+    max_len: Option<usize>,
+    shape_only: bool,
+}
+
+impl Default for Redaction {
+    /// Mask the [DEFAULT_MASKED_FIELDS], without truncating.
+    fn default() -> Self {
+        Self {
+            fields: DEFAULT_MASKED_FIELDS
+                .iter()
+                .map(|field| Cow::Borrowed(*field))
+                .collect(),
+            max_len: None,
+            shape_only: false,
+        }
+    }
+}
+
+impl Redaction {
+    /// Log queries and responses as they are.
+    pub fn disabled() -> Self {
+        Self {
+            fields: Vec::new(),
+            max_len: None,
+            shape_only: false,
+        }
+    }
+
+    /// Mask the values of the fields named `field`, at any depth.
+    /// Names are compared ignoring case.
+    pub fn mask(mut self, field: impl Into<Cow<'static, str>>) -> Self {
+        self.fields.push(field.into());
+        self
+    }
+
+    /// Truncate the logged messages to `max_len` bytes.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Only log the shape of queries, their terms and field names,
+    /// hiding every value, and the shape of the responses.
+    pub fn shape_only(mut self, shape_only: bool) -> Self {
+        self.shape_only = shape_only;
+        self
+    }
+
+    /// Render a query, serialized as `[type, term, options]`.
+    pub(crate) fn query(&self, query: &impl Serialize) -> String {
+        match serde_json::to_value(query) {
+            Ok(Value::Array(mut parts)) => {
+                if let Some(term) = parts.get_mut(1) {
+                    self.term(term);
+                }
+                if let Some(Value::Object(options)) = parts.get_mut(2) {
+                    for (key, value) in options.iter_mut() {
+                        self.field(key, value, Self::term);
+                    }
+                }
+                self.truncate(Value::Array(parts).to_string())
+            }
+            Ok(value) => self.truncate(value.to_string()),
+            Err(error) => format!("unserializable query: {}", error),
+        }
+    }
+
+    /// Render a single term, like the query attached to an error.
+    pub(crate) fn command(&self, command: &impl Serialize) -> String {
+        match serde_json::to_value(command) {
+            Ok(mut term) => {
+                self.term(&mut term);
+                self.truncate(term.to_string())
+            }
+            Err(error) => format!("unserializable query: {}", error),
+        }
+    }
+
+    /// Render a response body.
+    pub(crate) fn response(&self, body: &[u8]) -> String {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Object(mut response)) => {
+                if let Some(result) = response.get_mut("r") {
+                    self.value(result);
+                }
+                self.truncate(Value::Object(response).to_string())
+            }
+            _ => self.truncate(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+
+    /// Render an error, masking the fields of the documents quoted
+    /// in its message, or hiding the message in `shape_only` mode.
+    pub(crate) fn error(&self, error: &ReqlError) -> String {
+        if self.shape_only {
+            return HIDDEN.to_owned();
+        }
+        self.truncate(self.mask_text(&error.to_string()))
+    }
+
+    /// Mask the values of the masked fields written as `"field": value`
+    /// in a text, like the documents printed by the server.
+    fn mask_text(&self, text: &str) -> String {
+        let mut masked = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find('"') {
+            let len = match string_len(&rest[start..]) {
+                Some(len) => len,
+                None => break,
+            };
+            let (before, after) = rest.split_at(start + len);
+            masked.push_str(before);
+            rest = after;
+
+            let name = &before[start + 1..before.len() - 1];
+            let value = match after.trim_start().strip_prefix(':') {
+                Some(value) if self.masked(name) => value.trim_start(),
+                _ => continue,
+            };
+            masked.push_str(&after[..after.len() - value.len()]);
+            masked.push('"');
+            masked.push_str(REDACTED);
+            masked.push('"');
+            rest = &value[value_len(value)..];
+        }
+
+        masked.push_str(rest);
+        masked
+    }
+
+    fn truncate(&self, mut text: String) -> String {
+        if let Some(max_len) = self.max_len.filter(|max_len| text.len() > *max_len) {
+            let len = text.len();
+            let mut end = max_len;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text.truncate(end);
+            text.push_str(&format!("... ({} bytes)", len));
+        }
+        text
+    }
+
+    fn masked(&self, field: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|masked| masked.eq_ignore_ascii_case(field))
+    }
+
+    fn field(&self, key: &str, value: &mut Value, redact: fn(&Self, &mut Value)) {
+        if self.masked(key) {
+            *value = Value::String(REDACTED.to_owned());
+        } else {
+            redact(self, value);
+        }
+    }
+
+    /// Redact a term, `[type, args, options]`, keeping the term
+    /// types in `shape_only` mode.
+    fn term(&self, term: &mut Value) {
+        match term {
+            Value::Array(parts) => {
+                if let Some(Value::Array(args)) = parts.get_mut(1) {
+                    args.iter_mut().for_each(|arg| self.term(arg));
+                }
+                if let Some(Value::Object(options)) = parts.get_mut(2) {
+                    for (key, value) in options.iter_mut() {
+                        self.field(key, value, Self::term);
+                    }
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    self.field(key, value, Self::term);
+                }
+            }
+            scalar => self.scalar(scalar),
+        }
+    }
+
+    /// Redact a document.
+    fn value(&self, value: &mut Value) {
+        match value {
+            Value::Array(values) => values.iter_mut().for_each(|value| self.value(value)),
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    self.field(key, value, Self::value);
+                }
+            }
+            scalar => self.scalar(scalar),
+        }
+    }
+
+    fn scalar(&self, scalar: &mut Value) {
+        if self.shape_only {
+            *scalar = Value::String(HIDDEN.to_owned());
+        }
+    }
+}
+
+/// The length of the quoted string `text` starts with,
+/// quotes included.
+fn string_len(text: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The length of the value `text` starts with: a string, an
+/// object or an array, or a scalar ending at a delimiter.
+fn value_len(text: &str) -> usize {
+    if text.starts_with('"') {
+        return string_len(text).unwrap_or(text.len());
+    }
+    if !text.starts_with(['{', '[']) {
+        return text.find([',', '}', ']', '\n']).unwrap_or(text.len());
+    }
+
+    let mut depth = 0;
+    let mut index = 0;
+    while index < text.len() {
+        match text.as_bytes()[index] {
+            b'"' => {
+                index += string_len(&text[index..]).unwrap_or(text.len() - index);
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Redaction;
+    use crate::err::{ReqlError, ReqlRuntimeError};
+
+    #[test]
+    fn test_mask_fields() {
+        let query = json!([1, [56, [[15, ["users"]], {"id": "bob", "Password": "secret"}]], {}]);
+        let rendered = Redaction::default().query(&query);
+
+        assert_eq!(
+            rendered,
+            r#"[1,[56,[[15,["users"]],{"Password":"<redacted>","id":"bob"}]],{}]"#
+        );
+    }
+
+    #[test]
+    fn test_mask_command() {
+        let command = json!([56, [[15, ["users"]], {"id": "bob", "password": "secret"}]]);
+
+        assert_eq!(
+            Redaction::default().command(&command),
+            r#"[56,[[15,["users"]],{"id":"bob","password":"<redacted>"}]]"#
+        );
+    }
+
+    #[test]
+    fn test_shape_only() {
+        let redaction = Redaction::default().shape_only(true);
+        let query = json!([1, [16, [[15, ["users"]], "bob"]], {"db": [14, ["test"]]}]);
+        let response = br#"{"t":1,"r":[{"id":"bob","age":42,"tags":["a"]}]}"#;
+
+        assert_eq!(
+            redaction.query(&query),
+            r#"[1,[16,[[15,["?"]],"?"]],{"db":[14,["?"]]}]"#
+        );
+        assert_eq!(
+            redaction.response(response),
+            r#"{"r":[{"age":"?","id":"?","tags":["?"]}],"t":1}"#
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        let redaction = Redaction::disabled().max_len(8);
+
+        assert_eq!(
+            redaction.response(br#"{"t":1,"r":["long"]}"#),
+            r#"{"r":["l... (20 bytes)"#
+        );
+        assert_eq!(Redaction::disabled().response(b"plain"), "plain");
+    }
+
+    #[test]
+    fn test_mask_error() {
+        let message = r#"Duplicate primary key `id`:
+{
+	"id":	1,
+	"password":	"a \"secret\"",
+	"token": {"key": "}"},
+	"auth_key": 42
+}"#;
+        let error: ReqlError = ReqlRuntimeError::QueryLogic(message.into()).into();
+
+        assert_eq!(
+            Redaction::default().error(&error),
+            r#"runtime error; query logic; Duplicate primary key `id`:
+{
+	"id":	1,
+	"password":	"<redacted>",
+	"token": "<redacted>",
+	"auth_key": "<redacted>"
+}"#
+        );
+        assert_eq!(Redaction::default().shape_only(true).error(&error), "?");
+    }
+}
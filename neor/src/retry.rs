@@ -0,0 +1,300 @@
+//! Run queries again after transient errors.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use neor::arguments::{Conflict, InsertOption};
+//! use neor::retry::RetryPolicy;
+//! use neor::{args, r, Result};
+//! use serde_json::json;
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     let policy = RetryPolicy::default().max_attempts(5);
+//!
+//!     // reads are retried after any transient error
+//!     policy.run(&r.table("posts").get(1), &conn).await?;
+//!
+//!     // inserting with the ids and replacing on conflict can
+//!     // be applied twice, so the write is marked idempotent
+//!     let insert_option = InsertOption::default().conflict(Conflict::Replace);
+//!     let query = r.table("posts").insert(args!(json!({"id": 1}), insert_option));
+//!     policy.idempotent(true).run(&query, &conn).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tracing::debug;
+
+use crate::err::ReqlError;
+use crate::{Command, Result, Session};
+
+/// When and how often to run a query again
+///
+/// A failed query is run again while the error is
+/// [transient](ReqlError::is_transient), waiting longer between each
+/// attempt. Since a write may have been applied when it fails, it is
+/// only run again if the policy is marked [idempotent](Self::idempotent),
+/// or with [retry_unapplied_writes](Self::retry_unapplied_writes) after
+/// errors meaning that it was not applied.
+///
+/// A session whose connection is broken fails every query, so
+/// [run](Self::run) does not retry on it: connect a new session,
+/// or call [retry](Self::retry) with an operation which does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    max_elapsed: Option<Duration>,
+    idempotent: bool,
+    retry_unapplied_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, waiting 100 milliseconds then twice as long
+    /// each time, up to 5 seconds.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.,
+            max_elapsed: None,
+            idempotent: false,
+            retry_unapplied_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn never() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// The number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Wait `initial` before the second attempt, then `multiplier`
+    /// times longer before each new attempt, up to `max`.
+    pub fn backoff(mut self, initial: Duration, multiplier: f64, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.multiplier = multiplier.max(1.);
+        self.max_backoff = max;
+        self
+    }
+
+    /// Stop retrying once this long has passed since the first attempt.
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    /// Whether the writes can be applied more than once with the same
+    /// result, like an insert with client generated ids and
+    /// `Conflict::Replace`. Defaults to `false`.
+    pub fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Whether the writes which are not idempotent are run again after
+    /// an error meaning that they were not applied, like a refused
+    /// connection or an unavailable primary replica, see
+    /// [is_retryable](ReqlError::is_retryable). Defaults to `false`.
+    pub fn retry_unapplied_writes(mut self, retry_unapplied_writes: bool) -> Self {
+        self.retry_unapplied_writes = retry_unapplied_writes;
+        self
+    }
+
+    /// Whether a query failing with `error` should be run again,
+    /// `write` telling whether it writes.
+    pub fn should_retry(&self, error: &ReqlError, write: bool) -> bool {
+        if !write || self.idempotent {
+            error.is_transient()
+        } else {
+            self.retry_unapplied_writes && error.is_retryable()
+        }
+    }
+
+    /// Run the query, as [run](Command::run) does, retrying
+    /// according to the policy while the session is not broken.
+    pub async fn run(&self, query: &Command, session: &Session) -> Result<Option<Value>> {
+        let operation = || query.run(session);
+        self.retry_while(query.is_write(), operation, || !session.is_broken())
+            .await
+    }
+
+    /// Call `operation` until it succeeds or fails with an error which
+    /// should not be retried, `write` telling whether it writes.
+    pub async fn retry<T, F, Fut>(&self, write: bool, operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.retry_while(write, operation, || true).await
+    }
+
+    async fn retry_while<T, F, Fut>(
+        &self,
+        write: bool,
+        mut operation: F,
+        can_retry: impl Fn() -> bool,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let started = Instant::now();
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+
+        loop {
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            let elapsed = started.elapsed() + backoff;
+            let expired = matches!(self.max_elapsed, Some(max) if elapsed > max);
+            if attempt >= self.max_attempts
+                || expired
+                || !self.should_retry(&error, write)
+                || !can_retry()
+            {
+                return Err(error);
+            }
+
+            // the message may hold documents, the failed query logs it redacted
+            debug!(attempt, ?backoff, kind = error.kind(), "retrying query");
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.mul_f64(self.multiplier).min(self.max_backoff);
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::RetryPolicy;
+    use crate::err::{ReqlAvailabilityError, ReqlDriverError, ReqlError, ReqlRuntimeError};
+    use crate::{r, Session};
+
+    fn op_failed() -> ReqlError {
+        ReqlAvailabilityError::OpFailed("primary replica unavailable".into()).into()
+    }
+
+    fn op_indeterminate() -> ReqlError {
+        ReqlAvailabilityError::OpIndeterminate("response lost".into()).into()
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let refused = ReqlError::from(io::Error::from(io::ErrorKind::ConnectionRefused));
+        let reset = ReqlError::from(io::Error::from(io::ErrorKind::ConnectionReset));
+        let limit: ReqlError = ReqlRuntimeError::ResourceLimit("array too large".into()).into();
+
+        assert!(op_failed().is_retryable());
+        assert!(!op_indeterminate().is_retryable());
+        assert!(op_indeterminate().is_transient() && op_indeterminate().is_indeterminate());
+        assert!(ReqlError::from(ReqlDriverError::ConnectionBroken).is_indeterminate());
+        assert!(refused.is_retryable());
+        assert!(reset.is_transient() && reset.is_indeterminate());
+        assert!(!limit.is_transient());
+    }
+
+    #[test]
+    fn test_should_retry_writes() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(&op_indeterminate(), false));
+        assert!(!policy.should_retry(&op_indeterminate(), true));
+        assert!(!policy.should_retry(&op_failed(), true));
+        assert!(policy
+            .retry_unapplied_writes(true)
+            .should_retry(&op_failed(), true));
+        assert!(!policy
+            .retry_unapplied_writes(true)
+            .should_retry(&op_indeterminate(), true));
+        assert!(policy
+            .idempotent(true)
+            .should_retry(&op_indeterminate(), true));
+    }
+
+    #[test]
+    fn test_is_write() {
+        assert!(!r.table("posts").get(1).is_write());
+        assert!(r.table("posts").insert(json!({"id": 1})).is_write());
+        assert!(r.expr(1).do_(r.table("posts").delete(())).is_write());
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let policy = RetryPolicy::default()
+            .max_attempts(4)
+            .backoff(Duration::from_millis(1), 2., Duration::from_millis(2))
+            .retry_unapplied_writes(true);
+        let calls = AtomicUsize::new(0);
+
+        let result = policy
+            .retry(true, || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(op_failed()),
+                    _ => Ok(42),
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicUsize::new(0);
+        let result: Result<(), _> = policy
+            .retry(true, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(op_indeterminate())
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_broken_session() {
+        let session = Session::broken().await;
+        let policy = RetryPolicy::default().max_attempts(3).backoff(
+            Duration::from_secs(10),
+            2.,
+            Duration::from_secs(10),
+        );
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(1),
+            policy.run(&r.table("posts").get(1), &session),
+        )
+        .await
+        .expect("a broken session is not retried");
+
+        assert!(matches!(
+            result,
+            Err(ReqlError::Driver(ReqlDriverError::ConnectionBroken))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Queries built and serialized once, then run with other values.
+//!
+//! A [Template] is a query holding named parameters, created with
+//! [r.param](crate::r::param). The query is serialized when the
+//! template is created, and each [bind](Template::bind) only writes
+//! the values of the parameters into it.
+//!
+//! ```
+//! use neor::template::Template;
+//! use neor::{r, Result};
+//!
+//! async fn example() -> Result<()> {
+//!     let conn = r.connection().connect().await?;
+//!     let get_post = Template::new(&r.table("posts").get(r.param("id")).pluck("title"))?;
+//!
+//!     for id in 1..=3 {
+//!         let query = get_post.bind().param("id", id).query()?;
+//!         query.run(&conn).await?;
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::ser::{self, Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::err::ReqlDriverError;
+use crate::proto::{to_term_json, Datum, Query};
+use crate::{Command, Result};
+
+const PARAM_MARKER: &str = "neor-param:";
+
+thread_local! {
+    /// The parameters met while a template is serialized,
+    /// and the random key of their markers
+    static RENDERING: RefCell<Option<(String, Vec<String>)>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn param(name: impl Into<Cow<'static, str>>) -> Command {
+    Datum::Param(name.into()).into()
+}
+
+/// Write a parameter as a marker string when serializing a template,
+/// any other query with a parameter can not be serialized.
+pub(crate) fn serialize_param<S>(
+    name: &str,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let marker = RENDERING.with(|rendering| {
+        let mut rendering = rendering.borrow_mut();
+        let (key, names) = rendering.as_mut()?;
+        names.push(name.to_owned());
+        Some(format!("\0{}{}:{}", PARAM_MARKER, key, names.len() - 1))
+    });
+
+    match marker {
+        Some(marker) => marker.serialize(serializer),
+        None => Err(ser::Error::custom(format!(
+            "parameter `{}` is not bound, run the query with a `Template`",
+            name
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Json(String),
+    Param(String),
+}
+
+/// A query serialized once, whose parameters are bound on each run
+#[derive(Debug, Clone)]
+pub struct Template {
+    query: Command,
+    parts: Arc<[Part]>,
+}
+
+impl Template {
+    /// Serialize `query`, whose parameters are set with
+    /// [r.param](crate::r::param).
+    pub fn new(query: &Command) -> Result<Self> {
+        let key = uuid::Uuid::new_v4().simple().to_string();
+        RENDERING.with(|rendering| *rendering.borrow_mut() = Some((key.clone(), Vec::new())));
+        let json = serde_json::to_string(&Query(query));
+        let (_, names) = RENDERING
+            .with(|rendering| rendering.borrow_mut().take())
+            .unwrap_or_default();
+        let json = json?;
+
+        let mut parts = Vec::new();
+        let mut rest = json.as_str();
+        // serde_json escapes the NUL starting the marker
+        let marker = format!("\"\\u0000{}{}:", PARAM_MARKER, key);
+        while let Some(start) = rest.find(&marker) {
+            let after = &rest[start + marker.len()..];
+            let end = after.find('"').unwrap_or(after.len());
+            let name = after[..end]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| names.get(index))
+                .ok_or_else(|| ReqlDriverError::Other("invalid template parameter".to_owned()))?;
+
+            parts.push(Part::Json(rest[..start].to_owned()));
+            parts.push(Part::Param(name.clone()));
+            rest = &after[(end + 1).min(after.len())..];
+        }
+        parts.push(Part::Json(rest.to_owned()));
+
+        Ok(Self {
+            query: query.clone(),
+            parts: parts.into(),
+        })
+    }
+
+    /// The names of the parameters, in the order they appear in the query.
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Param(name) => Some(name.as_str()),
+            Part::Json(_) => None,
+        })
+    }
+
+    /// Start binding the values of the parameters.
+    pub fn bind(&self) -> Binding<'_> {
+        Binding {
+            template: self,
+            values: HashMap::new(),
+            error: None,
+        }
+    }
+}
+
+/// The values of the parameters of a [Template]
+#[derive(Debug)]
+pub struct Binding<'a> {
+    template: &'a Template,
+    values: HashMap<String, String>,
+    error: Option<crate::err::ReqlError>,
+}
+
+impl Binding<'_> {
+    /// Set the value of the parameter `name`.
+    pub fn param(mut self, name: impl Into<Cow<'static, str>>, value: impl Serialize) -> Self {
+        let name = name.into();
+        if self.error.is_some() {
+            return self;
+        }
+        if !self.template.params().any(|param| param == name) {
+            self.error = Some(ReqlDriverError::Other(format!("unknown parameter `{}`", name)).into());
+            return self;
+        }
+        match to_term_json(value) {
+            Ok(json) => {
+                self.values.insert(name.into_owned(), json);
+            }
+            Err(error) => self.error = Some(error),
+        }
+        self
+    }
+
+    /// The query of the template with the values of its parameters,
+    /// which must all be set.
+    pub fn query(self) -> Result<Command> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let len = self.template.parts.iter().map(|part| match part {
+            Part::Json(json) => json.len(),
+            Part::Param(name) => self.values.get(name).map_or(0, String::len),
+        });
+        let mut json = String::with_capacity(len.sum());
+        for part in self.template.parts.iter() {
+            match part {
+                Part::Json(part) => json.push_str(part),
+                Part::Param(name) => {
+                    let value = self.values.get(name).ok_or_else(|| {
+                        ReqlDriverError::Other(format!("parameter `{}` is not bound", name))
+                    })?;
+                    json.push_str(value);
+                }
+            }
+        }
+
+        Ok(self.template.query.with_prepared(RawValue::from_string(json)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Template;
+    use crate::proto::Query;
+    use crate::r;
+
+    #[test]
+    fn test_bind_params() {
+        let query = r.table("posts").get(r.param("id")).pluck(r.param("field"));
+        let template = Template::new(&query).unwrap();
+        assert_eq!(template.params().collect::<Vec<_>>(), ["id", "field"]);
+
+        let bound = template
+            .bind()
+            .param("id", 1)
+            .param("field", ["title", "view"])
+            .query()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(Query(&bound)).unwrap(),
+            json!([33, [[16, [[15, ["posts"], {}], 1]], [2, ["title", "view"]]]])
+        );
+        assert_eq!(bound.root_table(), Some("posts"));
+    }
+
+    #[test]
+    fn test_unbound_params() {
+        let query = r.table("posts").get(r.param("id"));
+        let template = Template::new(&query).unwrap();
+
+        assert!(template.bind().query().is_err());
+        assert!(template.bind().param("other", 1).query().is_err());
+        assert!(serde_json::to_string(&Query(&query)).is_err());
+    }
+}
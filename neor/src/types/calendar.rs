@@ -0,0 +1,197 @@
+use ql2::term::TermType;
+use serde::{Deserialize, Serialize};
+
+use crate::err::{ReqlDriverError, ReqlError};
+use crate::Command;
+
+/// Day of week, numbered from 1 (Monday) to 7 (Sunday)
+/// as returned by [day_of_week](crate::Command::day_of_week).
+///
+/// It is serialized as its number, and converts to the
+/// matching ReQL constant, such as `r.monday()`, as a `Command`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// Month of the year, numbered from 1 (January) to 12 (December)
+/// as returned by [month](crate::Command::month).
+///
+/// It is serialized as its number, and converts to the
+/// matching ReQL constant, such as `r.january()`, as a `Command`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Month {
+    January = 1,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Weekday {
+    pub(crate) fn term_type(self) -> TermType {
+        match self {
+            Self::Monday => TermType::Monday,
+            Self::Tuesday => TermType::Tuesday,
+            Self::Wednesday => TermType::Wednesday,
+            Self::Thursday => TermType::Thursday,
+            Self::Friday => TermType::Friday,
+            Self::Saturday => TermType::Saturday,
+            Self::Sunday => TermType::Sunday,
+        }
+    }
+}
+
+impl Month {
+    pub(crate) fn term_type(self) -> TermType {
+        match self {
+            Self::January => TermType::January,
+            Self::February => TermType::February,
+            Self::March => TermType::March,
+            Self::April => TermType::April,
+            Self::May => TermType::May,
+            Self::June => TermType::June,
+            Self::July => TermType::July,
+            Self::August => TermType::August,
+            Self::September => TermType::September,
+            Self::October => TermType::October,
+            Self::November => TermType::November,
+            Self::December => TermType::December,
+        }
+    }
+}
+
+impl TryFrom<u8> for Weekday {
+    type Error = ReqlError;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        match number {
+            1 => Ok(Self::Monday),
+            2 => Ok(Self::Tuesday),
+            3 => Ok(Self::Wednesday),
+            4 => Ok(Self::Thursday),
+            5 => Ok(Self::Friday),
+            6 => Ok(Self::Saturday),
+            7 => Ok(Self::Sunday),
+            _ => Err(ReqlDriverError::Time(format!("invalid day of week `{}`", number)).into()),
+        }
+    }
+}
+
+impl TryFrom<u8> for Month {
+    type Error = ReqlError;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        match number {
+            1 => Ok(Self::January),
+            2 => Ok(Self::February),
+            3 => Ok(Self::March),
+            4 => Ok(Self::April),
+            5 => Ok(Self::May),
+            6 => Ok(Self::June),
+            7 => Ok(Self::July),
+            8 => Ok(Self::August),
+            9 => Ok(Self::September),
+            10 => Ok(Self::October),
+            11 => Ok(Self::November),
+            12 => Ok(Self::December),
+            _ => Err(ReqlDriverError::Time(format!("invalid month `{}`", number)).into()),
+        }
+    }
+}
+
+impl From<Weekday> for u8 {
+    fn from(weekday: Weekday) -> Self {
+        weekday as u8
+    }
+}
+
+impl From<Month> for u8 {
+    fn from(month: Month) -> Self {
+        month as u8
+    }
+}
+
+impl From<time::Weekday> for Weekday {
+    fn from(weekday: time::Weekday) -> Self {
+        match weekday {
+            time::Weekday::Monday => Self::Monday,
+            time::Weekday::Tuesday => Self::Tuesday,
+            time::Weekday::Wednesday => Self::Wednesday,
+            time::Weekday::Thursday => Self::Thursday,
+            time::Weekday::Friday => Self::Friday,
+            time::Weekday::Saturday => Self::Saturday,
+            time::Weekday::Sunday => Self::Sunday,
+        }
+    }
+}
+
+impl From<time::Month> for Month {
+    fn from(month: time::Month) -> Self {
+        match month {
+            time::Month::January => Self::January,
+            time::Month::February => Self::February,
+            time::Month::March => Self::March,
+            time::Month::April => Self::April,
+            time::Month::May => Self::May,
+            time::Month::June => Self::June,
+            time::Month::July => Self::July,
+            time::Month::August => Self::August,
+            time::Month::September => Self::September,
+            time::Month::October => Self::October,
+            time::Month::November => Self::November,
+            time::Month::December => Self::December,
+        }
+    }
+}
+
+impl From<Weekday> for Command {
+    fn from(weekday: Weekday) -> Self {
+        Command::new(weekday.term_type())
+    }
+}
+
+impl From<Month> for Command {
+    fn from(month: Month) -> Self {
+        Command::new(month.term_type())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Month, Weekday};
+
+    #[test]
+    fn test_deserialize() {
+        let weekday: Weekday = serde_json::from_value(json!(6)).unwrap();
+        let month: Month = serde_json::from_value(json!(11)).unwrap();
+
+        assert_eq!(weekday, Weekday::Saturday);
+        assert_eq!(month, Month::November);
+        assert!(serde_json::from_value::<Weekday>(json!(0)).is_err());
+        assert!(serde_json::from_value::<Month>(json!(13)).is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        assert_eq!(serde_json::to_value(Weekday::Sunday).unwrap(), json!(7));
+        assert_eq!(serde_json::to_value(Month::March).unwrap(), json!(3));
+    }
+}
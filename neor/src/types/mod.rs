@@ -11,6 +11,7 @@ pub use crate::cmd::line::Line;
 pub use crate::cmd::point::Point;
 pub use crate::cmd::polygon::Polygon;
 pub use binary::Binary;
+pub use calendar::{Month, Weekday};
 pub use datetime::DateTime;
 pub use group_stream::{GroupedItem, GroupedStream};
 pub use time_::Time;
@@ -20,6 +21,7 @@ pub(crate) use datetime::timezone_to_string;
 pub use crate::Command;
 
 mod binary;
+mod calendar;
 mod datetime;
 mod group_stream;
 mod response_with_cmd;
@@ -3,24 +3,15 @@
 use neor::arguments::{Durability, InsertOption};
 use neor::{args, r, Command, Result, Session};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use uuid::Uuid;
 
 pub async fn set_up(with_data: bool) -> Result<(Session, Command, String)> {
     let table_name = Uuid::new_v4().to_string();
-    let user_rethinkdb = json!({
-        "id": "malik",
-        "password": "malik"
-    });
     let conn = r.connection().connect().await?;
     let table = r.table(table_name.as_str());
 
     r.table_create(table_name.as_str()).run(&conn).await?;
-    r.db("rethinkdb")
-        .table("users")
-        .insert(user_rethinkdb)
-        .run(&conn)
-        .await?;
+    r.user_create("malik", "malik").run(&conn).await?;
 
     if with_data {
         let data = Post::get_many_data();
@@ -36,12 +27,7 @@ pub async fn set_up(with_data: bool) -> Result<(Session, Command, String)> {
 
 pub async fn tear_down(conn: Session, table_name: &str) -> Result<()> {
     r.table_drop(table_name).run(&conn).await?;
-    r.db("rethinkdb")
-        .table("users")
-        .get("malik")
-        .delete(())
-        .run(&conn)
-        .await?;
+    r.user_drop("malik").run(&conn).await?;
     Ok(())
 }
 
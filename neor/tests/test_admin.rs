@@ -0,0 +1,49 @@
+use neor::admin::{self, ClusterConfig, DbConfig, Job, ServerConfig, ServerStatus, Stats, User};
+use neor::{r, Converter, Result};
+
+#[tokio::test]
+async fn test_admin_servers() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let config: Vec<ServerConfig> = admin::server_config().run(&conn).await?.unwrap().parse()?;
+    let status: Vec<ServerStatus> = admin::server_status().run(&conn).await?.unwrap().parse()?;
+
+    assert!(config.len() == status.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_cluster() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let config: ClusterConfig = admin::cluster_config()
+        .get("heartbeat")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let stats: Stats = admin::stats()
+        .get(["cluster"])
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let jobs: Vec<Job> = admin::jobs().run(&conn).await?.unwrap().parse()?;
+
+    assert!(config.heartbeat_timeout_secs > 0.);
+    assert!(stats.query_engine.is_some());
+    assert!(!jobs.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_users_and_dbs() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let users: Vec<User> = admin::users().run(&conn).await?.unwrap().parse()?;
+    let dbs: Vec<DbConfig> = admin::db_config().run(&conn).await?.unwrap().parse()?;
+
+    assert!(users.iter().any(|user| user.id == "admin"));
+    assert!(dbs.iter().any(|db| db.name == "test"));
+
+    Ok(())
+}
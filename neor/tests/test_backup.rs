@@ -0,0 +1,89 @@
+use std::io::Cursor;
+
+use neor::backup::{self, DumpOption, Format, RestoreOption};
+use neor::{r, Converter, Result};
+use serde_json::Value;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_dump_and_restore() -> Result<()> {
+    let (conn, _, table_name) = common::set_up(true).await?;
+    let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+    let options = DumpOption::default();
+
+    let dumped = backup::dump(&conn, "test", &table_name, &dir, &options).await?;
+    r.table_drop(table_name.as_str()).run(&conn).await?;
+    let restored =
+        backup::restore(&conn, "test", &table_name, &dir, &RestoreOption::default()).await?;
+    let indexes: Vec<String> = r
+        .table(table_name.as_str())
+        .index_list()
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(dumped == 5);
+    assert!(restored == 5);
+    assert!(indexes == vec!["title".to_owned()]);
+
+    std::fs::remove_dir_all(dir)?;
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_dump_and_restore_csv() -> Result<()> {
+    let (conn, _, table_name) = common::set_up(true).await?;
+    let restored_name = Uuid::new_v4().to_string();
+    let metadata = backup::table_metadata(&conn, "test", &table_name).await?;
+    let mut buffer = Vec::new();
+
+    let options = DumpOption::default()
+        .format(Format::Csv)
+        .fields(vec!["id".to_owned(), "title".to_owned()]);
+    let dumped = backup::dump_table(&conn, "test", &table_name, &options, &mut buffer).await?;
+
+    let options = RestoreOption::default().format(Format::Csv).chunk_size(2);
+    let reader = Cursor::new(buffer);
+    let restored =
+        backup::restore_table(&conn, "test", &restored_name, &metadata, &options, reader).await?;
+    let post: Value = r
+        .table(restored_name.as_str())
+        .get("1")
+        .run(&conn)
+        .await?
+        .unwrap();
+
+    assert!(dumped == 5);
+    assert!(restored == 5);
+    assert!(post == serde_json::json!({"id": "1", "title": "title1"}));
+
+    r.table_drop(restored_name.as_str()).run(&conn).await?;
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_dump_csv_with_different_fields() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let table_name = Uuid::new_v4().to_string();
+    let table = r.table(table_name.as_str());
+
+    r.table_create(table_name.as_str()).run(&conn).await?;
+    table
+        .insert(serde_json::json!([{"id": 1, "title": "title1"}, {"id": 2, "view": 3}]))
+        .run(&conn)
+        .await?;
+
+    let options = DumpOption::default().format(Format::Csv);
+    let inferred = backup::dump_table(&conn, "test", &table_name, &options, Vec::new()).await;
+    let options = options.fields(vec!["id".to_owned(), "title".to_owned(), "view".to_owned()]);
+    let declared = backup::dump_table(&conn, "test", &table_name, &options, Vec::new()).await?;
+
+    assert!(inferred.is_err());
+    assert!(declared == 2);
+
+    r.table_drop(table_name.as_str()).run(&conn).await?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::future;
+use neor::arguments::{BatcherOption, GetAllOption};
+use neor::intercept::Interceptor;
+use neor::{Command, Result};
+use serde_json::value::RawValue;
+
+use common::Post;
+
+mod common;
+
+#[derive(Default)]
+struct Queries(AtomicUsize);
+
+impl Interceptor for Queries {
+    fn response(&self, _query: &Command, _response: &RawValue) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_batcher_ops() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(true).await?;
+    let queries = Arc::new(Queries::default());
+    conn.add_interceptor(queries.clone());
+    let data = Post::get_many_data();
+    let batcher = table.batcher(&conn, BatcherOption::default());
+
+    let loads = [1, 3, 1, 9].map(|id| batcher.load::<Post>(id));
+    let posts = future::try_join_all(loads).await?;
+
+    assert!(
+        posts
+            == [
+                Some(data[0].clone()),
+                Some(data[2].clone()),
+                Some(data[0].clone()),
+                None
+            ]
+    );
+    assert!(queries.0.load(Ordering::SeqCst) == 1);
+
+    let post: Option<Post> = batcher.load(3).await?;
+    assert!(post == Some(data[2].clone()));
+    assert!(queries.0.load(Ordering::SeqCst) == 1);
+
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_batcher_with_index() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(true).await?;
+    let data = Post::get_many_data();
+    let options = BatcherOption::default()
+        .get_all(GetAllOption::default().index("title"))
+        .max_batch_size(1)
+        .cache(false);
+    let batcher = table.batcher(&conn, options);
+
+    let (title1, mut title4) = future::try_join(
+        batcher.load_many::<Post>("title1"),
+        batcher.load_many::<Post>("title4"),
+    )
+    .await?;
+    title4.sort_by_key(|post| post.id);
+
+    assert!(title1 == data[..1]);
+    assert!(title4 == data[3..]);
+
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_batcher_joins_running_query() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(true).await?;
+    let queries = Arc::new(Queries::default());
+    conn.add_interceptor(queries.clone());
+    let data = Post::get_many_data();
+    let options = BatcherOption::default().max_batch_size(1).cache(false);
+    let batcher = table.batcher(&conn, options);
+
+    // the first load is sent at once, the second one joins its query
+    let (first, second) =
+        future::try_join(batcher.load::<Post>(2), batcher.load::<Post>(2)).await?;
+
+    assert!(first == Some(data[1].clone()));
+    assert!(second == Some(data[1].clone()));
+    assert!(queries.0.load(Ordering::SeqCst) == 1);
+
+    common::tear_down(conn, &table_name).await
+}
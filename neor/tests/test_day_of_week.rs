@@ -1,4 +1,6 @@
-use neor::{r, Converter, Result};
+use neor::types::Weekday;
+use neor::{args, r, Converter, Result};
+use time::macros::{date, offset, time};
 
 #[tokio::test]
 async fn test_day_of_week_ops() -> Result<()> {
@@ -12,3 +14,32 @@ async fn test_day_of_week_ops() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_day_of_week_constants() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let date_time = r
+        .time(args!(date!(2022 - 11 - 26), time!(10:00), offset!(UTC)))
+        .cmd();
+
+    let day_of_week: Weekday = date_time.day_of_week().run(&conn).await?.unwrap().parse()?;
+    let is_saturday: bool = date_time
+        .day_of_week()
+        .eq(r.saturday())
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let is_typed_saturday: bool = date_time
+        .day_of_week()
+        .eq(Weekday::Saturday)
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(day_of_week == Weekday::Saturday);
+    assert!(is_saturday && is_typed_saturday);
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use serde_json::json;
 
-use neor::{func, r, Converter, Result};
+use neor::{func, r, Command, Converter, Result};
 
 use common::{set_up, tear_down, Post};
 
@@ -41,3 +41,21 @@ async fn test_filter_data_with_func() -> Result<()> {
 
     tear_down(conn, &table_name).await
 }
+
+#[tokio::test]
+async fn test_filter_data_with_closure() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let data_filtered: Vec<Post> = table
+        .filter(|user: Command| user.g("view").eq(r.expr(2)))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(data_filtered.len() == 2);
+    assert!(data_filtered.first() == data.get(3));
+    assert!(data_filtered.last() == data.get(1));
+
+    tear_down(conn, &table_name).await
+}
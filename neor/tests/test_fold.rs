@@ -1,4 +1,5 @@
-use neor::{args, func, r, Converter, Result};
+use neor::arguments::FoldOption;
+use neor::{args, func, r, Converter, Func, Result};
 
 use common::*;
 
@@ -29,3 +30,52 @@ async fn test_fold_ops() -> Result<()> {
 
     tear_down(conn, &table_name).await
 }
+
+#[tokio::test]
+async fn test_fold_ops_with_closure() -> Result<()> {
+    let posts = Post::get_many_data()
+        .into_iter()
+        .fold(String::new(), |acc, post| {
+            format!(
+                "{}{}{}",
+                acc,
+                if acc.is_empty() { "" } else { ", " },
+                post.title
+            )
+        });
+    let (conn, table, table_name) = set_up(true).await?;
+    let response: String = table
+        .order_by("id")
+        .fold(
+            "",
+            Func::from_fn2(|acc, post| {
+                acc.clone() + r.branch(acc.eq(""), args!("", ", ")) + post.g("title")
+            }),
+        )
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == posts);
+
+    tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_fold_ops_with_emit() -> Result<()> {
+    let posts: Vec<usize> = (1..=Post::get_many_data().len()).collect();
+    let (conn, table, table_name) = set_up(true).await?;
+    let fold_option = FoldOption::default().emit(func!(|_acc, _post, new_acc| r.array([new_acc])));
+    let response: Vec<usize> = table
+        .order_by("id")
+        .fold(0, args!(func!(|acc, _post| acc + 1), fold_option))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == posts);
+
+    tear_down(conn, &table_name).await
+}
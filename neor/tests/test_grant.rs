@@ -1,6 +1,7 @@
+use neor::admin::{PermissionScope, UserPermission};
 use neor::arguments::Permission;
 use neor::types::{ConfigChange, GrantChangeValue, GrantResponse};
-use neor::{Converter, Result};
+use neor::{r, Converter, Result};
 
 use common::{set_up, tear_down};
 
@@ -33,3 +34,34 @@ async fn test_grant_permission() -> Result<()> {
 
     tear_down(conn, &table_name).await
 }
+
+#[tokio::test]
+async fn test_revoke_permission() -> Result<()> {
+    let (conn, table, table_name) = set_up(false).await?;
+    let permissions = Permission::default().read(true).write(true);
+
+    table.grant("malik", permissions).run(&conn).await?;
+
+    let granted: Vec<UserPermission> = r
+        .permissions_for("malik")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let response: GrantResponse = table.revoke("malik").run(&conn).await?.unwrap().parse()?;
+    let revoked: Vec<UserPermission> = r
+        .permissions_for("malik")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(granted.iter().any(|permission| matches!(
+        permission.scope(),
+        PermissionScope::Table { ref table, .. } if table == table_name.as_str()
+    )));
+    assert!(response.granted == 1);
+    assert!(revoked.len() < granted.len());
+
+    tear_down(conn, &table_name).await
+}
@@ -1,3 +1,5 @@
+use futures::TryStreamExt;
+use neor::arguments::{HttpOption, Page};
 use neor::{args, r, Result};
 use serde_json::json;
 
@@ -17,11 +19,7 @@ async fn test_http_ops_with_params() -> Result<()> {
     let response = r
         .http(args!(
             "http://httpbin.org/get",
-            json!({
-                "params": {
-                    "user": 1
-                }
-            })
+            HttpOption::default().params(json!({"user": 1}))
         ))
         .run(&conn)
         .await?;
@@ -30,3 +28,43 @@ async fn test_http_ops_with_params() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_http_ops_with_pagination() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let http_option = HttpOption::default().page(Page::LinkNext).page_limit(2);
+    let mut query = r
+        .http(args!(
+            "https://api.github.com/repos/rethinkdb/rethinkdb/issues",
+            http_option
+        ))
+        .build_query(&conn);
+    let mut pages = 0;
+
+    while let Some(results) = query.try_next().await? {
+        assert!(results.is_array());
+        pages += 1;
+    }
+
+    assert!(pages > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_ops_with_pagination_as_array() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let http_option = HttpOption::default().page(Page::LinkNext).page_limit(2);
+    let response = r
+        .http(args!(
+            "https://api.github.com/repos/rethinkdb/rethinkdb/issues",
+            http_option
+        ))
+        .coerce_to("array")
+        .run(&conn)
+        .await?;
+
+    assert!(response.map_or(false, |results| results.is_array()));
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use neor::arguments::IndexCreateOption;
-use neor::types::IndexResponse;
+use neor::types::{IndexResponse, IndexStatusResponse};
 use neor::{args, r, Command, Converter, Result, Session};
 use uuid::Uuid;
 
@@ -24,6 +24,44 @@ async fn test_create_index_with_options() -> Result<()> {
     setup(&table_name, index_created, &conn).await
 }
 
+#[tokio::test]
+async fn test_create_index_with_binary_func() -> Result<()> {
+    let source_name = Uuid::new_v4().to_string();
+    let table_name = Uuid::new_v4().to_string();
+    let conn = r.connection().connect().await?;
+    let source = r.table(source_name.as_str());
+
+    r.table_create(source_name.as_str()).run(&conn).await?;
+    source.index_create("author").run(&conn).await?;
+    let status: Vec<IndexStatusResponse> = source
+        .index_status("author")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    r.table_drop(source_name.as_str()).run(&conn).await?;
+
+    let index_created = r
+        .table(table_name.as_str())
+        .index_create(args!("author", status[0].function.clone()));
+
+    setup(&table_name, index_created, &conn).await
+}
+
+#[tokio::test]
+async fn test_create_index_with_closure() -> Result<()> {
+    let table_name = Uuid::new_v4().to_string();
+    let conn = r.connection().connect().await?;
+    let index_option = IndexCreateOption::default().multi(true);
+    let index_created = r.table(table_name.as_str()).index_create(args!(
+        "author",
+        |row: Command| row.g("author").g("name"),
+        index_option
+    ));
+
+    setup(&table_name, index_created, &conn).await
+}
+
 /* #[tokio::test]
 async fn test_create_index_with_func() -> Result<()> {
     let table_name = Uuid::new_v4().to_string();
@@ -1,6 +1,6 @@
 use neor::arguments::{InsertOption, ReturnChanges};
 use neor::types::MutationResponse;
-use neor::{args, r, Converter, Result};
+use neor::{args, func, r, Converter, Result};
 use uuid::Uuid;
 
 use common::{set_up, tear_down, Post};
@@ -78,3 +78,27 @@ async fn test_insert_data_with_opts() -> Result<()> {
 
     tear_down(conn, &table_name).await
 }
+
+#[tokio::test]
+async fn test_insert_data_with_conflict_func() -> Result<()> {
+    let data = Post::get_one_data();
+    let (conn, table, table_name) = set_up(false).await?;
+    let insert_options = InsertOption::default().conflict_func(func!(|_id, old_doc, new_doc| {
+        old_doc.merge(r.object([r.expr("view"), old_doc.g("view") + new_doc.g("view")]))
+    }));
+
+    table.insert(&data).run(&conn).await?;
+
+    let data_inserted: MutationResponse = table
+        .insert(args!(&data, insert_options))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let response: Post = table.get(data.id).run(&conn).await?.unwrap().parse()?;
+
+    assert!(data_inserted.replaced == 1);
+    assert!(response.view == data.view * 2);
+
+    tear_down(conn, &table_name).await
+}
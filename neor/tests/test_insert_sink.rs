@@ -0,0 +1,47 @@
+use futures::{stream, SinkExt, StreamExt};
+use neor::arguments::InsertSinkOption;
+use neor::{Converter, Result};
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn test_insert_sink() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(false).await?;
+    let options = InsertSinkOption::default().batch_size(10).concurrency(2);
+    let mut sink = table.insert_sink(&conn, options);
+    let mut documents = stream::iter(0..95).map(|id| Ok(json!({ "id": id })));
+
+    sink.send_all(&mut documents).await?;
+    sink.close().await?;
+    let response = sink.into_response();
+
+    assert!(response.mutation.inserted == 95);
+    assert!(response.batches == 10);
+    assert!(response.batch_errors.is_empty());
+
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_insert_sink_aggregates_errors() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(true).await?;
+    let options = InsertSinkOption::default().batch_size(3);
+    let mut sink = table.insert_sink(&conn, options);
+
+    for id in 4..10 {
+        sink.send(json!({ "id": id })).await?;
+    }
+    sink.close().await?;
+    let response = sink.into_response();
+
+    assert!(response.mutation.inserted == 4);
+    assert!(response.mutation.errors == 2);
+    assert!(response.batch_errors.len() == 1);
+    assert!(response.batch_errors[0].batch == 0);
+
+    let count: usize = table.count(()).run(&conn).await?.unwrap().parse()?;
+    assert!(count == 9);
+
+    common::tear_down(conn, &table_name).await
+}
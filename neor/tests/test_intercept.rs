@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use neor::arguments::RunOption;
+use neor::err::{ReqlDriverError, ReqlError};
+use neor::intercept::Interceptor;
+use neor::{r, Command, Result};
+use serde_json::value::RawValue;
+
+#[derive(Default)]
+struct Counter {
+    responses: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+impl Interceptor for Counter {
+    fn response(&self, _query: &Command, _response: &RawValue) {
+        self.responses.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn error(&self, _query: &Command, _error: &ReqlError) {
+        self.errors.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+struct ScopeDb;
+
+impl Interceptor for ScopeDb {
+    fn before(&self, _query: &mut Command, options: &mut RunOption) -> Result<()> {
+        options.db = Some(neor::cmd::run::Db("rethinkdb".into()));
+        Ok(())
+    }
+}
+
+struct DenyWrites;
+
+impl Interceptor for DenyWrites {
+    fn before(&self, query: &mut Command, _options: &mut RunOption) -> Result<()> {
+        if query.is_write() {
+            return Err(ReqlDriverError::Other("denied".to_owned()).into());
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_intercept_ops() -> Result<()> {
+    let counter = Arc::new(Counter::default());
+    let conn = r
+        .connection()
+        .interceptor(counter.clone())
+        .interceptor(ScopeDb)
+        .connect()
+        .await?;
+
+    let response = r.table("users").run(&conn).await?;
+    assert!(response.is_some());
+    assert_eq!(counter.responses.load(Ordering::SeqCst), 1);
+
+    conn.add_interceptor(DenyWrites);
+    let response = r.db_create("denied").run(&conn).await;
+    assert!(response.is_err());
+    assert_eq!(counter.errors.load(Ordering::SeqCst), 0);
+
+    let response = r.table("not_found").run(&conn).await;
+    assert!(response.is_err());
+    assert_eq!(counter.errors.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
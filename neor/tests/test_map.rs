@@ -1,4 +1,4 @@
-use neor::{func, r, Converter, Result};
+use neor::{args, func, r, Command, Converter, Result};
 
 #[tokio::test]
 async fn test_map_ops() -> Result<()> {
@@ -15,3 +15,40 @@ async fn test_map_ops() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_map_ops_with_closure() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let response: Vec<u8> = r
+        .expr([1, 2, 3, 4, 5])
+        .map(|val: Command| val.clone() * val)
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == vec![1, 4, 9, 16, 25]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_map_sequences_with_closure() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let response: Vec<u32> = r
+        .map(
+            r.expr([100, 200, 300]),
+            args!(
+                [r.expr([10, 20, 30]), r.expr([1, 2, 3])],
+                |val1: Command, val2: Command, val3: Command| val1 + val2 + val3
+            ),
+        )
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == vec![111, 222, 333]);
+
+    Ok(())
+}
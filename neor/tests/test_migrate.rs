@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture};
+use neor::arguments::{IndexCreateOption, TableCreateOption};
+use neor::migrate::{self, Migration, MigrationState, Migrator};
+use neor::{r, Result, Session};
+use serde_json::json;
+use uuid::Uuid;
+
+struct CreateTable(String);
+
+impl Migration for CreateTable {
+    fn version(&self) -> u64 {
+        1
+    }
+
+    fn name(&self) -> &str {
+        "create table"
+    }
+
+    fn up<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let options = TableCreateOption::default();
+            migrate::ensure_table(session, "test", &self.0, options).await?;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            r.table_drop(self.0.as_str()).run(session).await?;
+            Ok(())
+        })
+    }
+}
+
+struct CreateIndex(String);
+
+impl Migration for CreateIndex {
+    fn version(&self) -> u64 {
+        2
+    }
+
+    fn name(&self) -> &str {
+        "create index"
+    }
+
+    fn up<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let table = r.table(self.0.as_str());
+            let options = IndexCreateOption::default();
+            migrate::ensure_index(session, &table, "title", None, options).await?;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            r.table(self.0.as_str())
+                .index_drop("title")
+                .run(session)
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+struct Slow;
+
+impl Migration for Slow {
+    fn version(&self) -> u64 {
+        1
+    }
+
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn up<'a>(&'a self, _session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, _session: &'a Session) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_up_and_down() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let table_name = Uuid::new_v4().to_string();
+    let migrations_table = Uuid::new_v4().to_string();
+    let migrator = Migrator::new("test")
+        .table(migrations_table.clone())
+        .migration(CreateIndex(table_name.clone()))
+        .migration(CreateTable(table_name.clone()));
+
+    let applied = migrator.up(&conn).await?;
+    let reapplied = migrator.up(&conn).await?;
+    let status = migrator.status(&conn).await?;
+    let rolled_back = migrator.down(&conn).await?;
+    let remaining = migrator.down_to(&conn, 0).await?;
+
+    assert!(applied == vec![1, 2]);
+    assert!(reapplied.is_empty());
+    assert!(status
+        .iter()
+        .all(|status| status.state == MigrationState::Applied && status.applied_at.is_some()));
+    assert!(rolled_back == Some(2));
+    assert!(remaining == vec![1]);
+
+    r.table_drop(migrations_table.as_str()).run(&conn).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_is_idempotent() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let table_name = Uuid::new_v4().to_string();
+    let options = TableCreateOption::default();
+
+    let created = migrate::ensure_table(&conn, "test", &table_name, options.clone()).await?;
+    let recreated = migrate::ensure_table(&conn, "test", &table_name, options).await?;
+
+    assert!(created);
+    assert!(!recreated);
+
+    r.table_drop(table_name.as_str()).run(&conn).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_status_is_read_only() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let table_name = Uuid::new_v4().to_string();
+    let migrations_table = Uuid::new_v4().to_string();
+    let migrator = Migrator::new("test")
+        .table(migrations_table.clone())
+        .migration(CreateTable(table_name));
+
+    let status = migrator.status(&conn).await?;
+    let tables: Vec<String> = r.table_list().run_as(&conn).await?.unwrap_or_default();
+
+    assert!(status.len() == 1 && status[0].state == MigrationState::Pending);
+    assert!(!tables.contains(&migrations_table));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_aborts_when_the_lock_is_stolen() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let migrations_table = Uuid::new_v4().to_string();
+    let migrator = Migrator::new("test")
+        .table(migrations_table.clone())
+        .lock_ttl(Duration::from_secs(2))
+        .migration(Slow);
+    let steal = async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        r.table(migrations_table.as_str())
+            .get("lock")
+            .update(json!({ "owner": "another instance" }))
+            .run(&conn)
+            .await
+    };
+
+    let (applied, stolen) = future::join(migrator.up(&conn), steal).await;
+    let status = migrator.status(&conn).await?;
+
+    assert!(stolen?.is_some());
+    assert!(applied.is_err());
+    assert!(status[0].state == MigrationState::Pending);
+
+    r.table_drop(migrations_table.as_str()).run(&conn).await?;
+    Ok(())
+}
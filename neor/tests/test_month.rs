@@ -1,4 +1,6 @@
-use neor::{r, Converter, Result};
+use neor::types::Month;
+use neor::{args, r, Converter, Result};
+use time::macros::{date, offset, time};
 
 #[tokio::test]
 async fn test_month_ops() -> Result<()> {
@@ -12,3 +14,32 @@ async fn test_month_ops() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_month_constants() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let date_time = r
+        .time(args!(date!(2022 - 11 - 26), time!(10:00), offset!(UTC)))
+        .cmd();
+
+    let month: Month = date_time.month().run(&conn).await?.unwrap().parse()?;
+    let is_november: bool = date_time
+        .month()
+        .eq(r.november())
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let is_typed_november: bool = date_time
+        .month()
+        .eq(Month::November)
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(month == Month::November);
+    assert!(is_november && is_typed_november);
+
+    Ok(())
+}
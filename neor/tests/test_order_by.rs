@@ -1,4 +1,4 @@
-use neor::{args, r, Converter, Result};
+use neor::{args, r, Command, Converter, Result};
 
 use common::{set_up, tear_down, Post};
 
@@ -20,6 +20,22 @@ async fn test_order_by_with_opts() -> Result<()> {
     tear_down(conn, &table_name).await
 }
 
+#[tokio::test]
+async fn test_order_by_desc_index() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let response: Vec<Post> = table
+        .order_by(r.index(r.desc("id")))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response.into_iter().rev().eq(data));
+
+    tear_down(conn, &table_name).await
+}
+
 #[tokio::test]
 async fn test_order_by_title_with_opts() -> Result<()> {
     let data = Post::get_many_data();
@@ -36,3 +52,19 @@ async fn test_order_by_title_with_opts() -> Result<()> {
 
     tear_down(conn, &table_name).await
 }
+
+#[tokio::test]
+async fn test_order_by_with_closure() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let response: Vec<Post> = table
+        .order_by(args!(|post: Command| post.g("title"), r.index("id")))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == data);
+
+    tear_down(conn, &table_name).await
+}
@@ -0,0 +1,97 @@
+use neor::cmd::paginate::{Cursor, PageIndex};
+use neor::{args, func, r, Result};
+
+use common::{set_up, tear_down, Post};
+
+mod common;
+
+#[tokio::test]
+async fn test_paginate_ops() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+
+    let page = table.paginate("id", 2, None).run::<Post>(&conn).await?;
+    assert!(page.items == data[..2]);
+
+    let after = page.next.map(|cursor| cursor.to_string());
+    let after = after.as_deref().map(str::parse::<Cursor>).transpose()?;
+    let page = table.paginate("id", 2, after).run::<Post>(&conn).await?;
+    assert!(page.items == data[2..4]);
+
+    let page = table
+        .paginate("id", 2, page.next)
+        .run::<Post>(&conn)
+        .await?;
+    assert!(page.items == data[4..]);
+    assert!(page.next.is_none());
+
+    tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_paginate_compound_desc() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let index_func = func!(|post| r.array([post.g("title"), post.g("id")]));
+
+    table
+        .index_create(args!("title_id", index_func))
+        .run(&conn)
+        .await?;
+    table.index_wait(()).run(&conn).await?;
+
+    let index = PageIndex::compound("title_id", ["title", "id"]).desc();
+    let mut after = None;
+    let mut posts = Vec::new();
+
+    loop {
+        let page = table
+            .paginate(index.clone(), 2, after)
+            .run::<Post>(&conn)
+            .await?;
+        posts.extend(page.items);
+
+        match page.next {
+            Some(cursor) => after = Some(cursor),
+            None => break,
+        }
+    }
+
+    assert!(posts.into_iter().rev().eq(data));
+
+    tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_paginate_duplicate_keys() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let index_func = func!(|post| r.array([post.g("title"), post.g("id")]));
+
+    table
+        .index_create(args!("title_id", index_func))
+        .run(&conn)
+        .await?;
+    table.index_wait(()).run(&conn).await?;
+
+    // the two posts titled `title4` are split between the pages
+    let index = PageIndex::compound("title_id", ["title", "id"]);
+    let page = table
+        .paginate(index.clone(), 4, None)
+        .run::<Post>(&conn)
+        .await?;
+    let last = table
+        .paginate(index, 4, page.next)
+        .run::<Post>(&conn)
+        .await?;
+
+    assert!(page.items == data[..4]);
+    assert!(last.items == data[4..]);
+    assert!(table
+        .paginate("title", 4, None)
+        .run::<Post>(&conn)
+        .await
+        .is_err());
+
+    tear_down(conn, &table_name).await
+}
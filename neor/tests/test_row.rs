@@ -0,0 +1,54 @@
+use neor::{r, Converter, Result};
+
+use common::{set_up, tear_down, Post};
+
+mod common;
+
+#[tokio::test]
+async fn test_row_in_filter() -> Result<()> {
+    let data = Post::get_many_data();
+    let (conn, table, table_name) = set_up(true).await?;
+    let data_filtered: Vec<Post> = table
+        .filter(r.row().g("view").eq(2))
+        .order_by("id")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(data_filtered.len() == 2);
+    assert!(data_filtered.first() == data.get(1));
+    assert!(data_filtered.last() == data.get(3));
+
+    tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_row_in_map() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let response: Vec<u8> = r
+        .expr([1, 2, 3, 4, 5])
+        .map(r.row() * 2)
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(response == vec![2, 4, 6, 8, 10]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_row_in_nested_function() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let response = r
+        .expr([[1, 2], [3, 4]])
+        .map(r.row().filter(r.row().gt(1)))
+        .run(&conn)
+        .await;
+
+    assert!(response.is_err());
+
+    Ok(())
+}
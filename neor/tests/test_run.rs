@@ -1,5 +1,7 @@
 use neor::arguments::{ReadMode, RunOption};
 use neor::{args, r, Result};
+use serde_json::json;
+use serde_json::value::RawValue;
 
 #[tokio::test]
 async fn test_run_ops() -> Result<()> {
@@ -15,3 +17,35 @@ async fn test_run_ops() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_run_with_db_ops() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let view = conn.with_db("rethinkdb");
+    let run_option = RunOption::default().read_mode(ReadMode::Outdated);
+
+    let response = r.table("users").run(&view).await?;
+    assert!(response.is_some());
+
+    let response = r
+        .table("users")
+        .run(&view.with_defaults(run_option))
+        .await?;
+    assert!(response.is_some());
+
+    assert!(r.table("users").run(&conn).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_as_ops() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let message: Option<String> = r.expr("hello").run_as(&conn).await?;
+    let raw: Option<Box<RawValue>> = r.expr(json!({"a": [1, 2]})).run_as(&conn).await?;
+
+    assert_eq!(message.as_deref(), Some("hello"));
+    assert_eq!(raw.unwrap().get(), r#"{"a":[1,2]}"#);
+
+    Ok(())
+}
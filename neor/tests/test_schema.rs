@@ -0,0 +1,71 @@
+use neor::arguments::Durability;
+use neor::migrate::{self, DbSpec, IndexSpec, Schema, SchemaChange, TableSpec, Unverified};
+use neor::{func, r, Converter, Func, Result};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_ensure_schema() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let table_name = Uuid::new_v4().to_string();
+    let schema = Schema::new().db(DbSpec::new("test").table(
+        TableSpec::new(table_name.clone())
+            .primary_key("uid")
+            .durability(Durability::Soft)
+            .write_hook(func!(|_, _, new_val| new_val))
+            .index(IndexSpec::simple("email"))
+            .index(IndexSpec::compound("full_name", ["last", "first"]))
+            .index(IndexSpec::multi("tags"))
+            .index(IndexSpec::geo("location"))
+            .index(IndexSpec::function(
+                "domain",
+                Func::from_fn1(|row| row.g("email").split("@").nth(1)),
+            )),
+    ));
+
+    let plan = migrate::ensure_schema(&conn, schema.clone().dry_run(true)).await?;
+    let tables: Vec<String> = r
+        .db("test")
+        .table_list()
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let applied = migrate::ensure_schema(&conn, schema.clone()).await?;
+    let reapplied = migrate::ensure_schema(&conn, schema).await?;
+
+    assert!(plan.dry_run);
+    assert!(plan.changes.len() == 7);
+    assert!(
+        plan.changes[0]
+            == SchemaChange::CreateTable {
+                db: "test".to_owned(),
+                table: table_name.clone()
+            }
+    );
+    assert!(!tables.contains(&table_name));
+    assert!(applied.changes == plan.changes);
+    assert!(plan.unverified.is_empty());
+    assert!(reapplied.changes.is_empty());
+    assert!(
+        reapplied.unverified
+            == vec![
+                Unverified::IndexFunction {
+                    db: "test".to_owned(),
+                    table: table_name.clone(),
+                    index: "full_name".to_owned()
+                },
+                Unverified::IndexFunction {
+                    db: "test".to_owned(),
+                    table: table_name.clone(),
+                    index: "domain".to_owned()
+                },
+                Unverified::WriteHook {
+                    db: "test".to_owned(),
+                    table: table_name.clone()
+                },
+            ]
+    );
+
+    r.table_drop(table_name.as_str()).run(&conn).await?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use neor::err::{ReqlError, ReqlRuntimeError};
+use neor::types::MutationResponse;
+use neor::{r, Command, Converter, Result};
+use serde_json::{json, Value};
+
+mod common;
+
+#[tokio::test]
+async fn test_update_if_version() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(false).await?;
+    table
+        .insert(json!({"id": 1, "title": "draft", "version": 1}))
+        .run(&conn)
+        .await?;
+
+    let response: MutationResponse = table
+        .get(1)
+        .update_if_version(1, json!({"title": "published"}))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let conflict = table
+        .get(1)
+        .update_if_version(1, json!({"title": "overwritten"}))
+        .run(&conn)
+        .await;
+    let post: Value = table.get(1).run(&conn).await?.unwrap();
+
+    assert!(response.replaced == 1);
+    assert!(matches!(
+        conflict,
+        Err(ReqlError::Runtime(ReqlRuntimeError::Conflict(_)))
+    ));
+    assert!(post == json!({"id": 1, "title": "published", "version": 2}));
+
+    common::tear_down(conn, &table_name).await
+}
+
+#[tokio::test]
+async fn test_update_if() -> Result<()> {
+    let (conn, table, table_name) = common::set_up(true).await?;
+
+    let response: MutationResponse = table
+        .get(1)
+        .update_if(|post: Command| post.g("view").eq(10), json!({"view": 11}))
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let conflict = table
+        .get(1)
+        .update_if(|post: Command| post.g("view").eq(10), json!({"view": 12}))
+        .run(&conn)
+        .await;
+    let user_error = r.error("conflict").run(&conn).await;
+    let many = table
+        .update_if(|post: Command| post.g("view").eq(10), json!({"view": 12}))
+        .run(&conn)
+        .await;
+
+    assert!(response.replaced == 1);
+    assert!(matches!(
+        conflict,
+        Err(ReqlError::Runtime(ReqlRuntimeError::Conflict(_)))
+    ));
+    assert!(matches!(
+        user_error,
+        Err(ReqlError::Runtime(ReqlRuntimeError::User(_)))
+    ));
+    assert!(many.is_err());
+
+    common::tear_down(conn, &table_name).await
+}
@@ -0,0 +1,36 @@
+use neor::admin::User;
+use neor::types::MutationResponse;
+use neor::{r, Converter, Result};
+
+#[tokio::test]
+async fn test_user_ops() -> Result<()> {
+    let conn = r.connection().connect().await?;
+    let created: MutationResponse = r
+        .user_create("tenant_42", "s3cret")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let updated: MutationResponse = r
+        .user_set_password("tenant_42", "n3w-s3cret")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+    let users: Vec<User> = r.user_list().run(&conn).await?.unwrap().parse()?;
+    let dropped: MutationResponse = r
+        .user_drop("tenant_42")
+        .run(&conn)
+        .await?
+        .unwrap()
+        .parse()?;
+
+    assert!(created.inserted == 1);
+    assert!(updated.replaced == 1);
+    assert!(users
+        .iter()
+        .any(|user| user.id == "tenant_42" && user.password));
+    assert!(dropped.deleted == 1);
+
+    Ok(())
+}